@@ -1,45 +1,779 @@
 //! Account endpoints for Robinhood crypto.
 //!
 //! Provides a minimal helper to fetch account information such as buying
-//! power and status.
+//! power and status, a preflight `Robinhood::health_check`, plus a valuation
+//! of the account's crypto holdings in its buying-power currency.
 
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use crate::auth::Robinhood;
+use reqwest::Method;
+use crate::error::{decode_response, RobinError, Result};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use crate::market_data::get_best_price_partial;
+use crate::trading::{get_all_crypto_holdings, CryptoOrder, OrderSide, PageParams};
 
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Whether the account can trade.
+pub enum AccountStatus {
+    Active,
+    Deactivated,
+    /// The account may only close positions.
+    SellOnly,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Basic account information for a Robinhood crypto account.
 pub struct AccountInfo{
     pub account_number: String,
-    pub status: String,
-    pub buying_power: String,
-    pub buying_power_currency: String,
+    pub status: AccountStatus,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub buying_power: Decimal,
+    pub buying_power_currency: Currency,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+/// Currency an account's buying power is held in. Orders are paid for in it,
+/// so it is also the quote currency of the pairs the account can trade.
+pub enum Currency {
+    Usd,
+    Eur,
+    Gbp,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl Currency {
+    /// The ISO 4217 code, e.g. `EUR`, as used in pair symbols.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Unknown(other) => other,
+        }
+    }
+}
+
+impl std::fmt::Display for Currency {
+    /// The ISO 4217 code, e.g. `USD`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::fmt::Display for AccountStatus {
+    /// The wire name, e.g. `sell_only`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Deactivated => "deactivated",
+            AccountStatus::SellOnly => "sell_only",
+            AccountStatus::Unknown(other) => other,
+        })
+    }
+}
+
+impl std::fmt::Display for AccountInfo {
+    /// One-line summary such as `account 123 [active] buying power 250.5 USD`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "account {} [{}] buying power {} {}",
+            self.account_number,
+            self.status,
+            self.buying_power.normalize(),
+            self.buying_power_currency
+        )
+    }
 }
 
 /// Fetch the authenticated crypto account's basic information.
 ///
 /// Returns account number, status, and buying power details.
-pub async fn get_account_info(rh: &Robinhood) -> Result<AccountInfo, reqwest::Error>{
+pub async fn get_account_info(rh: &Robinhood) -> Result<AccountInfo>{
     let path = "/api/v1/crypto/trading/accounts/";
-    let headers = rh.auth_headers(path, "GET", "");
-    let client = Client::new();
-    let resp = client
-        .get(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .send()
-        .await?.json::<AccountInfo>().await?;
-    Ok(resp)
+    let resp = rh.send(Method::GET, path, "").await?;
+    decode_response(resp)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Outcome of `Robinhood::health_check`.
+pub enum HealthState {
+    /// Credentials were accepted and the account is active.
+    Healthy,
+    /// Robinhood answered 401 or 403: the key or signature was refused.
+    AuthFailed,
+    /// No response arrived (connection refused, DNS, TLS, timeout, ...).
+    Unreachable,
+    /// Credentials work but the account can't trade normally.
+    AccountNotActive(AccountStatus),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Result of a preflight check of credentials, connectivity and account status.
+pub struct HealthStatus {
+    pub state: HealthState,
+    /// Whether Robinhood accepted the signed request.
+    pub authenticated: bool,
+    /// Round trip of the account request, or `None` if nothing came back.
+    pub latency: Option<Duration>,
+    /// The account as fetched, when authentication succeeded.
+    pub account: Option<AccountInfo>,
+}
+
+impl HealthStatus {
+    /// Whether it is safe to start trading.
+    pub fn is_healthy(&self) -> bool {
+        self.state == HealthState::Healthy
+    }
+}
+
+impl Robinhood {
+    /// The currency of the account's buying power.
+    ///
+    /// Fetched with `get_account_info` on first use, then cached for this
+    /// client and its clones.
+    pub async fn account_currency(&self) -> Result<Currency> {
+        self.account_currency
+            .get_or_try_init(|| async { Ok(get_account_info(self).await?.buying_power_currency) })
+            .await
+            .cloned()
+    }
+
+    /// Fail with `RobinError::InvalidArgument` unless `symbol`, quoted in
+    /// `quote_code`, is priced in the account's currency.
+    pub(crate) async fn require_account_currency(&self, symbol: &str, quote_code: &str) -> Result<()> {
+        let currency = self.account_currency().await?;
+        if currency.as_str() != quote_code {
+            return Err(RobinError::InvalidArgument(format!(
+                "{symbol} is quoted in {quote_code}, but the account's buying power is in {currency}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Confirm connectivity, credentials and account status in one call.
+    ///
+    /// Fetches the account once and reports the outcome as a `HealthStatus`
+    /// rather than an error, so a bot can gate its session on
+    /// `is_healthy()`. Other failures, such as a 5xx or an undecodable body,
+    /// are still returned as errors. `verify_self` is the offline counterpart
+    /// for catching a mismatched keypair before any request is sent.
+    pub async fn health_check(&self) -> Result<HealthStatus> {
+        let start = Instant::now();
+        let resp = match self.send(Method::GET, "/api/v1/crypto/trading/accounts/", "").await {
+            Ok(resp) => resp,
+            Err(RobinError::Http(_) | RobinError::Timeout(_)) => {
+                return Ok(HealthStatus { state: HealthState::Unreachable, authenticated: false, latency: None, account: None });
+            }
+            Err(e) => return Err(e),
+        };
+        let latency = Some(start.elapsed());
+        if matches!(resp.status.as_u16(), 401 | 403) {
+            return Ok(HealthStatus { state: HealthState::AuthFailed, authenticated: false, latency, account: None });
+        }
+        let account: AccountInfo = decode_response(resp)?;
+        let state = match &account.status {
+            AccountStatus::Active => HealthState::Healthy,
+            other => HealthState::AccountNotActive(other.clone()),
+        };
+        Ok(HealthStatus { state, authenticated: true, latency, account: Some(account) })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// One holding valued at the mid price of its pair in the account's currency.
+pub struct PositionValue {
+    pub asset_code: String,
+    pub quantity: Decimal,
+    /// Mid price of `{asset_code}-{currency}`, or `None` if no quote was returned.
+    pub price: Option<Decimal>,
+    /// `quantity * price`, or `None` for an unpriced asset.
+    pub value: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Value of the account's crypto holdings in its buying-power currency.
+pub struct PortfolioValue {
+    /// The account's currency, which `total` and every price are in.
+    pub currency: Currency,
+    /// Sum of every priced position; unpriced positions are left out.
+    pub total: Decimal,
+    pub positions: Vec<PositionValue>,
+}
+
+/// Value every crypto holding at the current mid price, in the account's
+/// buying-power currency.
+///
+/// Looks up the account's currency (see `Robinhood::account_currency`),
+/// fetches every page of holdings, then quotes every held asset's pair in
+/// that currency, e.g. `BTC-EUR`, with `get_best_price_partial`. Assets
+/// without a quote (delisted, or no pair in that currency) are reported with
+/// `price: None` and excluded from `total`, even when one of them makes
+/// Robinhood reject the batched request.
+pub async fn get_portfolio_value(rh: &Robinhood) -> Result<PortfolioValue> {
+    let currency = rh.account_currency().await?;
+    let holdings = get_all_crypto_holdings(rh, vec![], PageParams::default()).await?;
+    let pair = |asset_code: &str| format!("{asset_code}-{currency}");
+    let symbols: Vec<String> = holdings.iter().map(|h| pair(&h.asset_code)).collect();
+    let prices: HashMap<String, Decimal> = if symbols.is_empty() {
+        HashMap::new()
+    } else {
+        get_best_price_partial(rh, symbols.iter().map(String::as_str).collect()).await?
+            .results
+            .into_iter()
+            .map(|quote| (quote.symbol.clone(), quote.mid_price()))
+            .collect()
+    };
+
+    let positions: Vec<PositionValue> = holdings.into_iter()
+        .map(|holding| {
+            let price = prices.get(&pair(&holding.asset_code)).copied();
+            PositionValue {
+                value: price.map(|p| p * holding.total_quantity),
+                asset_code: holding.asset_code,
+                quantity: holding.total_quantity,
+                price,
+            }
+        })
+        .collect();
+    let total = positions.iter().filter_map(|p| p.value).sum();
+    Ok(PortfolioValue { currency, total, positions })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Which open lots a sell is matched against in `compute_cost_basis`.
+pub enum CostBasisMethod {
+    /// First in, first out: the oldest buys are sold first.
+    Fifo,
+    /// Last in, first out: the newest buys are sold first.
+    Lifo,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Cost basis and realized gains for one trading pair.
+pub struct CostBasis {
+    /// Asset quantity still held from matched buys.
+    pub quantity: Decimal,
+    /// What the still-held `quantity` cost, in the quote currency.
+    pub cost: Decimal,
+    /// Gains (negative for losses) from sells matched against earlier buys.
+    pub realized_pnl: Decimal,
+    /// Sold quantity with no earlier buy in the history to match, e.g. assets
+    /// bought before the history starts. It adds nothing to `realized_pnl`.
+    pub unmatched_sell_quantity: Decimal,
+}
+
+impl CostBasis {
+    /// Average cost per unit of the still-held quantity, or `None` if nothing is held.
+    pub fn average_cost(&self) -> Option<Decimal> {
+        self.cost.checked_div(self.quantity)
+    }
+}
+
+/// Match sells against buys in `orders` and report, per symbol (e.g.
+/// "BTC-USD"), the cost of what is still held and the realized gains.
+///
+/// Works from executions rather than order totals, so partially filled and
+/// canceled-after-partial-fill orders count exactly what was filled. Fills are
+/// replayed in timestamp order (string order without the `chrono` feature).
+/// Each execution's `effective_price` is used as-is: the API reports no
+/// separate fee, so any spread or fee Robinhood built into that price is part
+/// of the cost and proceeds.
+pub fn compute_cost_basis(orders: &[CryptoOrder], method: CostBasisMethod) -> HashMap<String, CostBasis> {
+    let mut fills: Vec<_> = orders.iter()
+        .flat_map(|order| order.executions.iter().map(move |e| (order, e)))
+        .collect();
+    fills.sort_by_key(|&(_, fill)| &fill.timestamp);
+
+    // open lots per symbol as (quantity, price)
+    let mut lots: HashMap<&str, VecDeque<(Decimal, Decimal)>> = HashMap::new();
+    let mut basis: HashMap<String, CostBasis> = HashMap::new();
+    for (order, fill) in fills {
+        let open = lots.entry(&order.symbol).or_default();
+        let entry = basis.entry(order.symbol.clone()).or_default();
+        match order.side {
+            OrderSide::Buy => open.push_back((fill.quantity, fill.effective_price)),
+            OrderSide::Sell => {
+                let mut remaining = fill.quantity;
+                while !remaining.is_zero() {
+                    let lot = match method {
+                        CostBasisMethod::Fifo => open.front_mut(),
+                        CostBasisMethod::Lifo => open.back_mut(),
+                    };
+                    let Some((lot_quantity, lot_price)) = lot else {
+                        entry.unmatched_sell_quantity += remaining;
+                        break;
+                    };
+                    let matched = remaining.min(*lot_quantity);
+                    entry.realized_pnl += (fill.effective_price - *lot_price) * matched;
+                    *lot_quantity -= matched;
+                    remaining -= matched;
+                    if lot_quantity.is_zero() {
+                        match method {
+                            CostBasisMethod::Fifo => open.pop_front(),
+                            CostBasisMethod::Lifo => open.pop_back(),
+                        };
+                    }
+                }
+            }
+            OrderSide::Unknown(_) => {}
+        }
+    }
+    for (symbol, open) in lots {
+        if let Some(entry) = basis.get_mut(symbol) {
+            entry.quantity = open.iter().map(|(quantity, _)| *quantity).sum();
+            entry.cost = open.iter().map(|(quantity, price)| quantity * price).sum();
+        }
+    }
+    basis
+}
+
+/// Synchronous versions of the account endpoints.
+///
+/// These use a blocking HTTP client and must not be called from inside an
+/// async runtime thread; use the async functions there instead.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use super::*;
+    use crate::error::decode_blocking_response;
+
+    /// Blocking version of [`super::get_account_info`].
+    pub fn get_account_info(rh: &Robinhood) -> Result<AccountInfo> {
+        let path = "/api/v1/crypto/trading/accounts/";
+        let resp = rh.send_blocking(Method::GET, path, "")?;
+        decode_blocking_response(resp)
+    }
 }
 
 #[tokio::test]
 async fn test_get_account_info(){
-    let rh = Robinhood::from_env();
+    let rh = crate::auth::cassette_robinhood("account_info");
     match get_account_info(&rh).await {
         Ok(info) => {
-            assert_eq!(info.status, "active");
-            assert_eq!(info.buying_power_currency, "USD");
+            assert_eq!(info.status, AccountStatus::Active);
+            assert_eq!(info.buying_power_currency, Currency::Usd);
         }
         Err(e) => panic!("error: {e}")
     }
 }
+
+#[tokio::test]
+async fn test_get_account_info_mock_server(){
+    use wiremock::matchers::{header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .and(header_exists("x-timestamp"))
+        .and(header_exists("x-signature"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "account_number": "123",
+            "status": "active",
+            "buying_power": "10.00",
+            "buying_power_currency": "USD"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let info = get_account_info(&rh).await.unwrap();
+    assert_eq!(info.account_number, "123");
+    assert_eq!(info.status, AccountStatus::Active);
+    assert_eq!(info.buying_power, Decimal::new(1000, 2));
+    assert_eq!(info.to_string(), "account 123 [active] buying power 10 USD");
+    crate::auth::assert_signed(&rh, &server.received_requests().await.unwrap()[0]);
+}
+
+#[tokio::test]
+async fn test_health_check_classifies_failures(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let account = |status: &str| serde_json::json!({
+        "account_number": "123", "status": status, "buying_power": "10", "buying_power_currency": "USD"
+    });
+    let check = |response: ResponseTemplate| async move {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crypto/trading/accounts/"))
+            .respond_with(response)
+            .expect(1)
+            .mount(&server)
+            .await;
+        crate::auth::test_robinhood(&server.uri()).health_check().await
+    };
+
+    let healthy = check(ResponseTemplate::new(200).set_body_json(account("active"))).await.unwrap();
+    assert!(healthy.is_healthy() && healthy.authenticated && healthy.latency.is_some());
+    assert_eq!(healthy.account.unwrap().account_number, "123");
+
+    let sell_only = check(ResponseTemplate::new(200).set_body_json(account("sell_only"))).await.unwrap();
+    assert_eq!(sell_only.state, HealthState::AccountNotActive(AccountStatus::SellOnly));
+    assert!(sell_only.authenticated && !sell_only.is_healthy());
+
+    let refused = check(ResponseTemplate::new(401).set_body_string("{\"detail\":\"bad signature\"}")).await.unwrap();
+    assert_eq!(refused.state, HealthState::AuthFailed);
+    assert!(!refused.authenticated && refused.latency.is_some() && refused.account.is_none());
+
+    assert!(matches!(check(ResponseTemplate::new(500)).await, Err(RobinError::Api { status: 500, .. })));
+
+    // nothing listening on the port
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let down = crate::auth::test_robinhood(&format!("http://{addr}")).health_check().await.unwrap();
+    assert_eq!(down.state, HealthState::Unreachable);
+    assert_eq!((down.authenticated, down.latency), (false, None));
+}
+
+#[tokio::test]
+async fn test_get_account_info_retries_transient_errors(){
+    use std::time::Duration;
+    use crate::retry::RetryConfig;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "account_number": "123",
+            "status": "active",
+            "buying_power": "10.00",
+            "buying_power_currency": "USD"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri()).with_retry(RetryConfig {
+        max_retries: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+    });
+    let info = get_account_info(&rh).await.unwrap();
+    assert_eq!(info.status, AccountStatus::Active);
+}
+
+#[tokio::test]
+async fn test_rate_limit_headers_are_recorded(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200)
+            .insert_header("x-ratelimit-limit", "100")
+            .insert_header("x-ratelimit-remaining", "42")
+            .insert_header("x-ratelimit-reset", "30")
+            .set_body_json(serde_json::json!({
+                "account_number": "123",
+                "status": "active",
+                "buying_power": "10.00",
+                "buying_power_currency": "USD"
+            })))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    assert!(rh.last_rate_limit().is_none());
+    get_account_info(&rh).await.unwrap();
+    let rl = rh.last_rate_limit().unwrap();
+    assert_eq!((rl.limit, rl.remaining), (100, 42));
+    assert!(rl.reset > std::time::SystemTime::now());
+}
+
+#[tokio::test]
+async fn test_slow_response_times_out(){
+    use std::time::Duration;
+    use crate::error::RobinError;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri()).with_timeout(Duration::from_millis(50));
+    match get_account_info(&rh).await {
+        Err(RobinError::Timeout(_)) => {}
+        other => panic!("expected timeout, got {:?}", other.err()),
+    }
+}
+
+#[tokio::test]
+async fn test_client_config_http2_prior_knowledge(){
+    use std::time::Duration;
+    use crate::auth::ClientConfig;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "account_number": "123",
+            "status": "active",
+            "buying_power": "1.00",
+            "buying_power_currency": "USD"
+        })))
+        .mount(&server)
+        .await;
+
+    // h2c without an upgrade: the request only succeeds if HTTP/2 is spoken from the start
+    let config = ClientConfig {
+        http2_prior_knowledge: true,
+        pool_max_idle_per_host: 1,
+        tcp_keepalive: Some(Duration::from_secs(30)),
+        ..ClientConfig::default()
+    };
+    let rh = crate::auth::test_robinhood(&server.uri()).with_client_config(config).unwrap();
+    assert_eq!(get_account_info(&rh).await.unwrap().account_number, "123");
+}
+
+#[cfg(feature = "blocking")]
+#[tokio::test]
+async fn test_blocking_get_account_info(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "account_number": "123",
+            "status": "active",
+            "buying_power": "100.00",
+            "buying_power_currency": "USD"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // the blocking client must be created and dropped off the runtime thread
+    let uri = server.uri();
+    let info = tokio::task::spawn_blocking(move || {
+        let rh = crate::auth::test_robinhood(&uri);
+        blocking::get_account_info(&rh)
+    }).await.unwrap().unwrap();
+    assert_eq!(info.account_number, "123");
+    crate::auth::assert_signed(&crate::auth::test_robinhood(&server.uri()), &server.received_requests().await.unwrap()[0]);
+}
+
+#[tokio::test]
+async fn test_get_portfolio_value(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let holding = |asset: &str, qty: f64| serde_json::json!({
+        "account_number": "123",
+        "asset_code": asset,
+        "total_quantity": qty,
+        "quantity_available_for_trading": qty
+    });
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "account_number": "123", "status": "active", "buying_power": "10", "buying_power_currency": "EUR"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/holdings/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": null, "previous": null,
+            "results": [holding("BTC", 0.5), holding("ODD", 10.0)]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/best_bid_ask/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-EUR",
+            "price": 100.0,
+            "bid_inclusive_of_sell_spread": 99.0,
+            "sell_spread": 0.01,
+            "ask_inclusive_of_buy_spread": 101.0,
+            "buy_spread": 0.01,
+            "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let portfolio = get_portfolio_value(&rh).await.unwrap();
+    assert_eq!(portfolio.currency, Currency::Eur);
+    assert_eq!(portfolio.total, Decimal::from(50));
+    assert_eq!(portfolio.positions[0].price, Some(Decimal::from(100)));
+    assert_eq!(portfolio.positions[1].asset_code, "ODD");
+    assert_eq!(portfolio.positions[1].value, None);
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests[2].url.query(), Some("symbol=BTC-EUR&symbol=ODD-EUR"));
+
+    // the currency is cached, so a second valuation doesn't refetch the account
+    get_portfolio_value(&rh).await.unwrap();
+    assert_eq!(serde_json::from_str::<Currency>("\"CHF\"").unwrap(), Currency::Unknown("CHF".into()));
+}
+
+#[tokio::test]
+async fn test_get_portfolio_value_survives_a_rejected_pair(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(crate::trading::account_json("EUR")))
+        .mount(&server)
+        .await;
+    let holding = |asset: &str, qty: f64| serde_json::json!({
+        "account_number": "123", "asset_code": asset, "total_quantity": qty, "quantity_available_for_trading": qty
+    });
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/holdings/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": null, "previous": null, "results": [holding("BTC", 0.5), holding("GONE", 10.0)]
+        })))
+        .mount(&server)
+        .await;
+    // a delisted pair makes Robinhood reject any request that names it
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/best_bid_ask/"))
+        .and(query_param("symbol", "GONE-EUR"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "type": "validation_error", "errors": [{"attr": "symbol", "detail": "Invalid symbol GONE-EUR."}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/best_bid_ask/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-EUR", "price": 100.0,
+            "bid_inclusive_of_sell_spread": 99.0, "sell_spread": 0.01,
+            "ask_inclusive_of_buy_spread": 101.0, "buy_spread": 0.01,
+            "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let portfolio = get_portfolio_value(&rh).await.unwrap();
+    assert_eq!(portfolio.total, Decimal::from(50));
+    assert_eq!(portfolio.positions[0].price, Some(Decimal::from(100)));
+    assert_eq!((portfolio.positions[1].asset_code.as_str(), portfolio.positions[1].price), ("GONE", None));
+}
+
+#[tokio::test]
+async fn test_request_and_response_hooks_observe_calls(){
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (requests, responses) = (seen.clone(), seen.clone());
+    let rh = crate::auth::test_robinhood(&server.uri())
+        .on_request(move |method, path| requests.lock().unwrap().push(format!("{method} {path}")))
+        .on_response(move |status, _latency| responses.lock().unwrap().push(status.to_string()));
+    assert!(get_account_info(&rh).await.is_err());
+    assert_eq!(*seen.lock().unwrap(), ["GET /api/v1/crypto/trading/accounts/", "503 Service Unavailable"]);
+}
+
+#[tokio::test]
+async fn test_get_account_info_with_mock_transport(){
+    use std::sync::Arc;
+    use crate::transport::MockTransport;
+
+    let mock = Arc::new(MockTransport::new());
+    mock.push_response(200, r#"{"account_number":"123","status":"active","buying_power":"5.5","buying_power_currency":"USD"}"#);
+    let rh = crate::auth::test_robinhood("https://example.invalid").with_transport(mock.clone());
+    let info = get_account_info(&rh).await.unwrap();
+    assert_eq!(info.buying_power, Decimal::new(55, 1));
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].method, Method::GET);
+    assert_eq!(requests[0].url, "https://example.invalid/api/v1/crypto/trading/accounts/");
+    assert!(requests[0].headers.contains_key("x-signature"));
+}
+
+#[cfg(feature = "strict-schema")]
+#[test]
+fn test_strict_schema_rejects_unknown_fields(){
+    let body = serde_json::json!({
+        "account_number": "123",
+        "status": "active",
+        "buying_power": "1.00",
+        "buying_power_currency": "USD",
+        "new_field": true
+    });
+    let err = serde_json::from_value::<AccountInfo>(body).unwrap_err();
+    assert!(err.to_string().contains("new_field"));
+}
+
+#[test]
+fn test_compute_cost_basis_fifo_and_lifo() {
+    let order = |symbol: &str, side: &str, fills: &[(&str, &str, &str)]| -> CryptoOrder {
+        let executions: Vec<_> = fills.iter()
+            .map(|(price, quantity, ts)| serde_json::json!({"effective_price": price, "quantity": quantity, "timestamp": ts}))
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "id": format!("{symbol}-{side}"), "account_number": "1", "symbol": symbol, "client_order_id": "c",
+            "side": side, "executions": executions, "type": "market", "state": "filled",
+            "average_price": null, "filled_asset_quantity": "0",
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+            "market_order_config": null, "limit_order_config": null,
+            "stop_loss_order_config": null, "stop_limit_order_config": null
+        })).unwrap()
+    };
+    let d = |s: &str| s.parse::<Decimal>().unwrap();
+    // listed out of order; the sell is two partial fills
+    let orders = [
+        order("BTC-USD", "sell", &[("300", "1", "2024-01-03T00:00:00Z"), ("300", "0.5", "2024-01-03T00:00:01Z")]),
+        order("BTC-USD", "buy", &[("100", "1", "2024-01-01T00:00:00Z")]),
+        order("BTC-USD", "buy", &[("200", "1", "2024-01-02T00:00:00Z")]),
+        order("ETH-USD", "sell", &[("10", "2", "2024-01-01T00:00:00Z")]),
+    ];
+
+    let fifo = compute_cost_basis(&orders, CostBasisMethod::Fifo);
+    let btc = &fifo["BTC-USD"];
+    assert_eq!((btc.quantity, btc.cost, btc.realized_pnl), (d("0.5"), d("100"), d("250")));
+    assert_eq!(btc.average_cost(), Some(d("200")));
+
+    let lifo = compute_cost_basis(&orders, CostBasisMethod::Lifo);
+    let btc = &lifo["BTC-USD"];
+    assert_eq!((btc.quantity, btc.cost, btc.realized_pnl), (d("0.5"), d("50"), d("200")));
+    assert_eq!(btc.average_cost(), Some(d("100")));
+
+    let eth = &lifo["ETH-USD"];
+    assert_eq!((eth.unmatched_sell_quantity, eth.realized_pnl), (d("2"), Decimal::ZERO));
+    assert_eq!(eth.average_cost(), None);
+}
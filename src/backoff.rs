@@ -0,0 +1,102 @@
+//! Exponential backoff with jitter.
+//!
+//! The retry layer and the best-price subscription both wait between
+//! attempts using this policy. It is public so callers driving their own
+//! loops (reconnects, rate-limit waits) can back off the same way.
+
+use std::time::Duration;
+
+/// Jittered exponential backoff, usable as an endless iterator of delays.
+///
+/// Delay number `n` (starting at 0) is `base * factor^n`, capped at `max`,
+/// then reduced by a random share of up to `jitter` (0.0 to 1.0) so
+/// concurrent clients don't retry in lockstep. With the default jitter of 0.5
+/// each delay falls in `[capped / 2, capped]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max: Duration,
+    pub factor: f64,
+    pub jitter: f64,
+    attempt: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(250), Duration::from_secs(10))
+    }
+}
+
+impl Backoff {
+    /// Double from `base` up to `max`, with a jitter of 0.5.
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self { base, max, factor: 2.0, jitter: 0.5, attempt: 0 }
+    }
+
+    /// Grow each delay by `factor` instead of doubling it.
+    pub fn with_factor(mut self, factor: f64) -> Self {
+        self.factor = factor;
+        self
+    }
+
+    /// Take up to `jitter` (clamped to 0.0..=1.0) off each delay at random.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay before attempt number `attempt` (starting at 0), independent of
+    /// where the iterator is.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = self.factor.powi(attempt.min(i32::MAX as u32) as i32);
+        let capped = Duration::try_from_secs_f64(self.base.as_secs_f64() * exp)
+            .unwrap_or(self.max)
+            .min(self.max);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        capped - capped.mul_f64(jitter * fastrand::f64())
+    }
+
+    /// Start the sequence over, e.g. after a successful attempt.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let delay = self.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        Some(delay)
+    }
+}
+
+#[test]
+fn test_backoff_is_monotonic_up_to_max() {
+    let max = Duration::from_millis(1000);
+    let delays: Vec<_> = Backoff::new(Duration::from_millis(10), max).with_factor(3.0).with_jitter(0.0).take(12).collect();
+    assert_eq!(delays[..3], [Duration::from_millis(10), Duration::from_millis(30), Duration::from_millis(90)]);
+    assert!(delays.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(*delays.last().unwrap(), max);
+    assert_eq!(Backoff::new(Duration::from_secs(1), max).with_jitter(0.0).delay(u32::MAX), max);
+
+    let mut backoff = Backoff::default().with_jitter(0.0);
+    backoff.nth(4);
+    backoff.reset();
+    assert_eq!(backoff.next(), Some(Duration::from_millis(250)));
+}
+
+#[test]
+fn test_backoff_jitter_stays_within_bounds() {
+    let backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(800)).with_jitter(0.25);
+    for attempt in 0..6 {
+        let capped = (Duration::from_millis(100) * 2u32.pow(attempt)).min(backoff.max);
+        for _ in 0..50 {
+            let delay = backoff.delay(attempt);
+            assert!(delay >= capped.mul_f64(0.75) && delay <= capped, "attempt {attempt}: {delay:?}");
+        }
+    }
+    let wild = Backoff::default().with_jitter(7.0);
+    assert!(wild.delay(0) <= Duration::from_millis(250));
+}
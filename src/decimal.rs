@@ -0,0 +1,128 @@
+//! Serde adapters for `Decimal` fields that `rust_decimal::serde` doesn't
+//! cover, and the canonical form used when a `Decimal` goes into a URL.
+
+use rust_decimal::Decimal;
+
+/// Format `value` for a path or query string: plain fixed-point, no exponent,
+/// no trailing fractional zeros, and never `-0`.
+///
+/// The result is both signed and sent, so it must be produced once and reused.
+pub(crate) fn to_api_string(value: &Decimal) -> String {
+    value.normalize().to_string()
+}
+
+/// Decode a `Decimal` from either a JSON number (`101.5`) or a numeric
+/// string (`"101.5"`, `"1e-7"`), whichever the endpoint happens to send.
+///
+/// Each submodule pairs this with the upstream serializer for one wire
+/// format, so a field keeps serializing exactly as before:
+/// `str`/`float` for required fields and `str_option`/`float_option` for
+/// optional ones (which also take `null`).
+pub(crate) mod flexible_decimal {
+    use std::fmt;
+    use std::str::FromStr;
+    use rust_decimal::Decimal;
+    use serde::de::{self, Visitor};
+    use serde::{Deserialize, Deserializer};
+
+    struct FlexibleVisitor;
+
+    impl Visitor<'_> for FlexibleVisitor {
+        type Value = Decimal;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal number or numeric string")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+            let v = v.trim();
+            Decimal::from_str(v)
+                .or_else(|_| Decimal::from_scientific(v))
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+            Ok(Decimal::from(v))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+            Ok(Decimal::from(v))
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+            // the shortest round-trip form, so 0.1 decodes as exactly 0.1
+            Decimal::from_str(&v.to_string()).map_err(|_| E::invalid_value(de::Unexpected::Float(v), &self))
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Decimal, D::Error> {
+        d.deserialize_any(FlexibleVisitor)
+    }
+
+    pub fn deserialize_option<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Decimal>, D::Error> {
+        #[derive(Deserialize)]
+        struct Flexible(#[serde(deserialize_with = "deserialize")] Decimal);
+        Ok(Option::<Flexible>::deserialize(d)?.map(|Flexible(value)| value))
+    }
+
+    /// Serializes as a string.
+    pub mod str {
+        pub use rust_decimal::serde::str::serialize;
+        pub use super::deserialize;
+    }
+
+    /// Serializes as a number.
+    pub mod float {
+        pub use rust_decimal::serde::float::serialize;
+        pub use super::deserialize;
+    }
+
+    /// Serializes `Some` as a string.
+    pub mod str_option {
+        pub use rust_decimal::serde::str_option::serialize;
+        pub use super::deserialize_option as deserialize;
+    }
+
+    /// Serializes `Some` as a number.
+    pub mod float_option {
+        pub use rust_decimal::serde::float_option::serialize;
+        pub use super::deserialize_option as deserialize;
+    }
+}
+
+#[test]
+fn test_flexible_decimal_accepts_numbers_and_strings() {
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct Quote {
+        #[serde(with = "flexible_decimal::str")]
+        price: Decimal,
+        #[serde(with = "flexible_decimal::float_option", default)]
+        spread: Option<Decimal>,
+    }
+    let parse = |json: &str| {
+        let q = serde_json::from_str::<Quote>(json).unwrap();
+        (q.price, q.spread)
+    };
+    let expected = (Decimal::new(1015, 1), Some(Decimal::new(1, 3)));
+    assert_eq!(parse(r#"{"price": "101.5", "spread": 0.001}"#), expected);
+    assert_eq!(parse(r#"{"price": 101.5, "spread": "0.001"}"#), expected);
+    assert_eq!(parse(r#"{"price": "1.015e2", "spread": "1e-3"}"#), expected);
+    assert_eq!(parse(r#"{"price": 7, "spread": null}"#), (Decimal::from(7), None));
+    assert_eq!(parse(r#"{"price": "7"}"#), (Decimal::from(7), None));
+    assert!(serde_json::from_str::<Quote>(r#"{"price": "lots"}"#).is_err());
+    assert!(serde_json::from_str::<Quote>(r#"{"price": true}"#).is_err());
+
+    // serialization keeps each field's wire format
+    let q = Quote { price: Decimal::new(1015, 1), spread: Some(Decimal::new(1, 3)) };
+    assert_eq!(serde_json::to_string(&q).unwrap(), r#"{"price":"101.5","spread":0.001}"#);
+}
+
+#[test]
+fn test_to_api_string_is_plain_fixed_point() {
+    assert_eq!(to_api_string(&Decimal::new(100, 8)), "0.000001");
+    assert_eq!(to_api_string(&Decimal::new(1, 28)), "0.0000000000000000000000000001");
+    assert_eq!(to_api_string(&Decimal::MAX), "79228162514264337593543950335");
+    assert_eq!(to_api_string(&Decimal::new(25_000_000, 2)), "250000");
+    assert_eq!(to_api_string(&-Decimal::new(0, 3)), "0");
+    assert_eq!(to_api_string(&Decimal::from_scientific("1e-7").unwrap()), "0.0000001");
+}
@@ -0,0 +1,266 @@
+//! Error types shared by every endpoint helper.
+//!
+//! All public functions return `Result<T, RobinError>`, so callers can tell a
+//! network failure apart from an API rejection or a malformed response body.
+
+use std::fmt;
+use crate::transport::HttpResponse;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::trading::{CryptoOrder, OrderValidationError};
+
+/// Errors that can occur while talking to the Robinhood crypto API.
+#[derive(Debug)]
+pub enum RobinError {
+    /// Transport-level failure (connection, TLS, reading the body, ...).
+    Http(reqwest::Error),
+    /// The request or connection attempt exceeded its configured timeout.
+    Timeout(reqwest::Error),
+    /// Robinhood answered with a non-2xx status; `body` holds the raw error
+    /// detail, `error` its structured form when it could be parsed, and
+    /// `request_id` the response's `x-request-id` for support tickets.
+    Api { status: u16, body: String, error: Option<ApiError>, request_id: Option<String> },
+    /// The requested resource (e.g. an order id) does not exist; holds the response body.
+    NotFound(String),
+    /// A 2xx response body could not be decoded into the expected type.
+    Decode(serde_json::Error),
+    /// Credentials are missing or malformed.
+    Auth(String),
+    /// An argument was rejected locally, before any request was sent.
+    InvalidArgument(String),
+    /// Order parameters failed `CreateCyptoOrderParams::validate`; nothing was sent.
+    InvalidOrder(OrderValidationError),
+    /// An order was still working when a polling deadline passed; holds the
+    /// last observed order.
+    OrderTimeout(Box<CryptoOrder>),
+    /// The order has already finished (filled, canceled, ...), so there is
+    /// nothing left to act on; holds the order as found.
+    OrderTerminal(Box<CryptoOrder>),
+    /// `Robinhood::shutdown` was called, so no new requests are sent.
+    ShuttingDown,
+    /// The response wasn't JSON, e.g. an HTML maintenance or Cloudflare page;
+    /// `snippet` holds the start of the body.
+    UnexpectedContentType { status: u16, content_type: String, snippet: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// Structured error body Robinhood returns for rejected requests, e.g.
+/// `{"type": "validation_error", "errors": [{"attr": "asset_quantity", "detail": "..."}]}`.
+pub struct ApiError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    #[serde(default)]
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+/// One reason a request was rejected, optionally tied to a request field.
+pub struct FieldError {
+    #[serde(default)]
+    pub attr: Option<String>,
+    pub detail: String,
+}
+
+impl RobinError {
+    /// Build an `Api` error, parsing `body` into an `ApiError` when it has that shape.
+    pub(crate) fn api(status: u16, body: String, request_id: Option<String>) -> Self {
+        let error = serde_json::from_str(&body).ok();
+        RobinError::Api { status, body, error, request_id }
+    }
+}
+
+/// Convenience alias used throughout the crate. The error type defaults to
+/// `RobinError` but can be given, so a glob import doesn't break
+/// `Result<T, E>` elsewhere.
+pub type Result<T, E = RobinError> = std::result::Result<T, E>;
+
+impl fmt::Display for RobinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RobinError::Http(e) => write!(f, "http error: {e}"),
+            RobinError::Timeout(e) => write!(f, "request timed out: {e}"),
+            RobinError::Api { status, body, error, request_id } => {
+                match error {
+                    Some(error) if !error.errors.is_empty() => {
+                        write!(f, "api error ({status}) {}:", error.error_type)?;
+                        for (i, e) in error.errors.iter().enumerate() {
+                            let sep = if i == 0 { " " } else { "; " };
+                            match &e.attr {
+                                Some(attr) => write!(f, "{sep}{attr}: {}", e.detail)?,
+                                None => write!(f, "{sep}{}", e.detail)?,
+                            }
+                        }
+                    }
+                    _ => write!(f, "api error ({status}): {body}")?,
+                }
+                match request_id {
+                    Some(id) => write!(f, " [request id {id}]"),
+                    None => Ok(()),
+                }
+            }
+            RobinError::NotFound(body) => write!(f, "not found: {body}"),
+            RobinError::Decode(e) => write!(f, "decode error: {e}"),
+            RobinError::Auth(msg) => write!(f, "auth error: {msg}"),
+            RobinError::InvalidArgument(msg) => write!(f, "invalid argument: {msg}"),
+            RobinError::InvalidOrder(e) => write!(f, "invalid order: {e}"),
+            RobinError::OrderTimeout(order) => {
+                write!(f, "order {} still {:?} when the deadline passed", order.id, order.state)
+            }
+            RobinError::OrderTerminal(order) => write!(f, "order {} is already {}", order.id, order.state),
+            RobinError::ShuttingDown => write!(f, "client is shutting down"),
+            RobinError::UnexpectedContentType { status, content_type, snippet } => {
+                write!(f, "expected JSON but got {content_type} ({status}): {snippet}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RobinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RobinError::Http(e) | RobinError::Timeout(e) => Some(e),
+            RobinError::Decode(e) => Some(e),
+            RobinError::InvalidOrder(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RobinError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            RobinError::Timeout(e)
+        } else {
+            RobinError::Http(e)
+        }
+    }
+}
+
+impl From<OrderValidationError> for RobinError {
+    fn from(e: OrderValidationError) -> Self {
+        RobinError::InvalidOrder(e)
+    }
+}
+
+impl From<serde_json::Error> for RobinError {
+    fn from(e: serde_json::Error) -> Self {
+        RobinError::Decode(e)
+    }
+}
+
+/// How much of an unexpected body `RobinError::UnexpectedContentType` keeps.
+const SNIPPET_LEN: usize = 300;
+
+/// Check the response status and decode a successful body as JSON.
+///
+/// Non-2xx responses are returned as `RobinError::Api` with the body captured
+/// verbatim (and parsed into `ApiError` when possible) instead of being
+/// parsed into the success type. A body that isn't JSON, judged by its
+/// `content-type`, is `RobinError::UnexpectedContentType` instead.
+pub(crate) fn decode_response<T: DeserializeOwned>(resp: HttpResponse) -> Result<T> {
+    check_content_type(resp.status, &resp.headers, &resp.body)?;
+    if !resp.status.is_success() {
+        let request_id = resp.request_id().map(str::to_string);
+        return Err(RobinError::api(resp.status.as_u16(), resp.body, request_id));
+    }
+    Ok(serde_json::from_str(&resp.body)?)
+}
+
+/// Blocking counterpart of `decode_response`.
+#[cfg(feature = "blocking")]
+pub(crate) fn decode_blocking_response<T: DeserializeOwned>(resp: reqwest::blocking::Response) -> Result<T> {
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let request_id = crate::transport::request_id(&headers).map(str::to_string);
+    let body = resp.text()?;
+    check_content_type(status, &headers, &body)?;
+    if !status.is_success() {
+        return Err(RobinError::api(status.as_u16(), body, request_id));
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Reject a body that can't be what the API meant to send.
+///
+/// A success must be JSON. An error may be plain text, which is still the
+/// API's own detail and stays `RobinError::Api`, but an HTML page comes from
+/// something in front of it (a maintenance page, a Cloudflare challenge).
+/// A missing `content-type` is given the benefit of the doubt.
+fn check_content_type(status: StatusCode, headers: &HeaderMap, body: &str) -> Result<()> {
+    let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return Ok(());
+    };
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    let is_json = mime == "application/json" || mime.ends_with("+json");
+    let unexpected = if status.is_success() { !is_json } else { mime == "text/html" };
+    if !unexpected {
+        return Ok(());
+    }
+    let mut end = body.len().min(SNIPPET_LEN);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    Err(RobinError::UnexpectedContentType {
+        status: status.as_u16(),
+        content_type: content_type.to_string(),
+        snippet: body[..end].trim().to_string(),
+    })
+}
+
+#[test]
+fn test_api_error_display() {
+    let err = RobinError::api(400, "{\"detail\":\"bad\"}".to_string(), None);
+    assert_eq!(err.to_string(), "api error (400): {\"detail\":\"bad\"}");
+    let err = RobinError::api(503, "down".to_string(), Some("req-1".to_string()));
+    assert_eq!(err.to_string(), "api error (503): down [request id req-1]");
+}
+
+#[test]
+fn test_api_error_parses_validation_errors() {
+    let body = r#"{"type":"validation_error","errors":[{"attr":"asset_quantity","detail":"Quantity too small."},{"detail":"Bad order."}]}"#;
+    let err = RobinError::api(400, body.to_string(), None);
+    match &err {
+        RobinError::Api { error: Some(error), .. } => {
+            assert_eq!(error.error_type, "validation_error");
+            assert_eq!(error.errors[0].attr.as_deref(), Some("asset_quantity"));
+        }
+        other => panic!("expected parsed api error, got {other:?}"),
+    }
+    assert_eq!(err.to_string(), "api error (400) validation_error: asset_quantity: Quantity too small.; Bad order.");
+}
+
+#[test]
+fn test_html_page_is_unexpected_content_type() {
+    use reqwest::header::HeaderValue;
+
+    let page = format!("<!DOCTYPE html><html><title>Down for maintenance</title>{}</html>", "x".repeat(500));
+    let response = |status: u16, content_type: &'static str, body: &str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        HttpResponse { status: StatusCode::from_u16(status).unwrap(), headers, body: body.to_string() }
+    };
+
+    for status in [200, 503] {
+        match decode_response::<serde_json::Value>(response(status, "text/html; charset=UTF-8", &page)) {
+            Err(RobinError::UnexpectedContentType { status: got, content_type, snippet }) => {
+                assert_eq!((got, content_type.as_str()), (status, "text/html; charset=UTF-8"));
+                assert_eq!(snippet.len(), SNIPPET_LEN);
+                assert!(snippet.starts_with("<!DOCTYPE html><html><title>Down for maintenance"));
+            }
+            other => panic!("expected an unexpected content type error, got {other:?}"),
+        }
+    }
+    let err = decode_response::<serde_json::Value>(response(200, "text/html", "<html>oops</html>")).unwrap_err();
+    assert_eq!(err.to_string(), "expected JSON but got text/html (200): <html>oops</html>");
+
+    // JSON, JSON-flavoured types and plain-text API errors decode as before
+    let ok: serde_json::Value = decode_response(response(200, "application/json; charset=utf-8", "{\"ok\":true}")).unwrap();
+    assert_eq!(ok["ok"], true);
+    assert!(decode_response::<serde_json::Value>(response(200, "application/problem+json", "{}")).is_ok());
+    let api = decode_response::<serde_json::Value>(response(400, "text/plain", "invalid symbol"));
+    assert!(matches!(api, Err(RobinError::Api { status: 400, .. })));
+}
@@ -0,0 +1,161 @@
+//! CSV export of orders and holdings for spreadsheets (`csv` feature).
+//!
+//! Decimals are written in plain fixed-point without trailing zeros, and
+//! timestamps as `YYYY-MM-DD HH:MM:SS` in UTC, which Excel and Google Sheets
+//! both read as dates. Errors come from the `csv` crate, which also wraps any
+//! I/O error from `writer`.
+
+use std::io::Write;
+use rust_decimal::Decimal;
+use crate::time::Timestamp;
+use crate::trading::{config_terms, CryptoHoldings, CryptoOrder};
+
+/// Write one row per order, summarizing its executions in the fill columns.
+///
+/// Columns: `id`, `client_order_id`, `symbol`, `side`, `type`, `state`,
+/// `asset_quantity`, `quote_amount`, `limit_price`, `stop_price`,
+/// `filled_asset_quantity`, `average_price`, `filled_value`, `executions`,
+/// `created_at_utc`, `updated_at_utc`, `reject_reason`. Use
+/// `executions_to_csv` for one row per fill instead.
+pub fn orders_to_csv<W: Write>(orders: &[CryptoOrder], writer: W) -> csv::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record([
+        "id", "client_order_id", "symbol", "side", "type", "state",
+        "asset_quantity", "quote_amount", "limit_price", "stop_price",
+        "filled_asset_quantity", "average_price", "filled_value", "executions",
+        "created_at_utc", "updated_at_utc", "reject_reason",
+    ])?;
+    for order in orders {
+        let (quote_amount, asset_quantity, stop_price, limit_price) = config_terms(
+            &order.market_order_config,
+            &order.limit_order_config,
+            &order.stop_loss_order_config,
+            &order.stop_limit_order_config,
+        );
+        csv.write_record([
+            order.id.clone(),
+            order.client_order_id.clone(),
+            order.symbol.clone(),
+            order.side.to_string(),
+            order.order_type.to_string(),
+            order.state.to_string(),
+            decimal(asset_quantity),
+            decimal(quote_amount),
+            decimal(limit_price),
+            decimal(stop_price),
+            decimal(order.filled_asset_quantity),
+            decimal(order.average_price.or_else(|| order.average_fill_price())),
+            decimal(Some(order.total_filled_value())),
+            order.executions.len().to_string(),
+            timestamp(&order.created_at),
+            timestamp(&order.updated_at),
+            order.reject_reason.clone().unwrap_or_default(),
+        ])?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Write one row per execution across `orders`, repeating the order's id,
+/// symbol and side on each.
+///
+/// Columns: `order_id`, `symbol`, `side`, `effective_price`, `quantity`,
+/// `value`, `timestamp_utc`. Orders with no fills produce no rows.
+pub fn executions_to_csv<W: Write>(orders: &[CryptoOrder], writer: W) -> csv::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(["order_id", "symbol", "side", "effective_price", "quantity", "value", "timestamp_utc"])?;
+    for order in orders {
+        for execution in &order.executions {
+            csv.write_record([
+                order.id.clone(),
+                order.symbol.clone(),
+                order.side.to_string(),
+                decimal(Some(execution.effective_price)),
+                decimal(Some(execution.quantity)),
+                decimal(Some(execution.effective_price * execution.quantity)),
+                timestamp(&execution.timestamp),
+            ])?;
+        }
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Write one row per holding.
+///
+/// Columns: `account_number`, `asset_code`, `total_quantity`,
+/// `quantity_available_for_trading`, `locked_quantity`.
+pub fn holdings_to_csv<W: Write>(holdings: &[CryptoHoldings], writer: W) -> csv::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(["account_number", "asset_code", "total_quantity", "quantity_available_for_trading", "locked_quantity"])?;
+    for holding in holdings {
+        csv.write_record([
+            holding.account_number.clone(),
+            holding.asset_code.clone(),
+            decimal(Some(holding.total_quantity)),
+            decimal(Some(holding.quantity_available_for_trading)),
+            decimal(Some(holding.locked_quantity())),
+        ])?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Plain fixed-point without trailing zeros; empty when absent.
+fn decimal(value: Option<Decimal>) -> String {
+    value.map(|d| d.normalize().to_string()).unwrap_or_default()
+}
+
+/// `YYYY-MM-DD HH:MM:SS[.fff]` in UTC.
+#[cfg(feature = "chrono")]
+fn timestamp(ts: &Timestamp) -> String {
+    ts.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+}
+
+/// `YYYY-MM-DD HH:MM:SS[.fff]` for UTC (`Z`) strings; other offsets are kept as received.
+#[cfg(not(feature = "chrono"))]
+fn timestamp(ts: &Timestamp) -> String {
+    match ts.strip_suffix('Z') {
+        Some(utc) => utc.replacen('T', " ", 1),
+        None => ts.clone(),
+    }
+}
+
+#[test]
+fn test_orders_and_holdings_to_csv() {
+    let order: CryptoOrder = serde_json::from_value(serde_json::json!({
+        "id": "o1", "account_number": "1", "symbol": "BTC-USD", "client_order_id": "c1",
+        "side": "buy", "type": "limit", "state": "filled",
+        "executions": [
+            {"effective_price": "100.00", "quantity": "0.00000001", "timestamp": "2024-01-01T00:00:00Z"},
+            {"effective_price": "110", "quantity": "3", "timestamp": "2024-01-01T00:00:01.5Z"}
+        ],
+        "average_price": null, "filled_asset_quantity": "3.00000001",
+        "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:01Z",
+        "market_order_config": null, "stop_loss_order_config": null, "stop_limit_order_config": null,
+        "limit_order_config": {"asset_quantity": "3.00000001", "limit_price": "120.00"}
+    })).unwrap();
+
+    let mut out = Vec::new();
+    orders_to_csv(std::slice::from_ref(&order), &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let row = out.lines().nth(1).unwrap();
+    assert!(row.starts_with("o1,c1,BTC-USD,buy,limit,filled,3.00000001,,120,,3.00000001,"), "{row}");
+    assert!(row.ends_with(",330.000001,2,2024-01-01 00:00:00,2024-01-01 00:00:01,"), "{row}");
+
+    let mut out = Vec::new();
+    executions_to_csv(&[order], &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.lines().nth(1), Some("o1,BTC-USD,buy,100,0.00000001,0.000001,2024-01-01 00:00:00"));
+    assert_eq!(out.lines().count(), 3);
+
+    let holding: CryptoHoldings = serde_json::from_value(serde_json::json!({
+        "account_number": "1", "asset_code": "BTC", "total_quantity": 1.5, "quantity_available_for_trading": 1.0
+    })).unwrap();
+    let mut out = Vec::new();
+    holdings_to_csv(&[holding], &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "account_number,asset_code,total_quantity,quantity_available_for_trading,locked_quantity\n1,BTC,1.5,1,0.5\n"
+    );
+}
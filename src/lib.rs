@@ -2,11 +2,29 @@
 //!
 //! This crate provides simple, async Rust bindings for a subset of Robinhood's
 //! crypto endpoints, including authentication, account info, market data, and
-//! trading utilities. See individual modules for details.
+//! trading utilities. See individual modules for details, or start with
+//! `use robinrust::prelude::*;`.
 
 extern crate core;
 
+pub mod error;
 pub mod auth;
 pub mod account;
 pub mod market_data;
-pub mod trading;
\ No newline at end of file
+pub mod trading;
+pub mod retry;
+pub mod backoff;
+pub mod rate_limit;
+pub mod time;
+pub mod transport;
+pub mod registry;
+pub mod symbol;
+pub mod partial;
+pub mod prelude;
+#[cfg(feature = "csv")]
+pub mod export;
+
+mod decimal;
+mod query;
+mod request;
+mod shutdown;
\ No newline at end of file
@@ -1,38 +1,86 @@
 //! Market data endpoints for crypto symbols.
 //!
 //! This module provides helpers to query best bid/ask and estimated prices
-//! from the Robinhood crypto market data API.
+//! from the Robinhood crypto market data API. See `stream` for continuous
+//! best bid/ask updates.
 
-use reqwest::Client;
 use serde::{Serialize, Deserialize};
 use crate::auth::Robinhood;
+use reqwest::Method;
+use crate::time::Timestamp;
+use crate::error::{decode_response, RobinError, Result};
+use crate::decimal::to_api_string;
+use crate::query::path_with_query;
+use crate::partial::{fetch_partial, PartialResult};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
+use futures::StreamExt;
 
-#[derive(Debug, Serialize, Deserialize)]
+pub mod stream;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Best bid/ask snapshot for a symbol from Robinhood.
 pub struct BestPriceResult {
     pub symbol: String,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub price: Decimal,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub bid_inclusive_of_sell_spread: Decimal,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub sell_spread: Decimal,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub ask_inclusive_of_buy_spread: Decimal,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub buy_spread: Decimal,
 
-    pub timestamp: String,
+    #[serde(with = "crate::time::rfc3339")]
+    pub timestamp: Timestamp,
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+impl BestPriceResult {
+    /// Midpoint of the bid and ask, both inclusive of spread.
+    pub fn mid_price(&self) -> Decimal {
+        (self.bid_inclusive_of_sell_spread + self.ask_inclusive_of_buy_spread) / Decimal::TWO
+    }
+
+    /// Ask minus bid. Negative if the book is crossed.
+    pub fn spread(&self) -> Decimal {
+        self.ask_inclusive_of_buy_spread - self.bid_inclusive_of_sell_spread
+    }
+
+    /// Spread in basis points of the mid price, or zero if the mid is zero.
+    pub fn spread_bps(&self) -> Decimal {
+        self.spread()
+            .checked_div(self.mid_price())
+            .map_or(Decimal::ZERO, |ratio| ratio * Decimal::from(10_000))
+    }
+}
+
+impl std::fmt::Display for BestPriceResult {
+    /// One-line summary such as `BTC-USD bid/ask 64990/65010 spread 20`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} bid/ask {}/{} spread {}",
+            self.symbol,
+            self.bid_inclusive_of_sell_spread.normalize(),
+            self.ask_inclusive_of_buy_spread.normalize(),
+            self.spread().normalize()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Response wrapper containing best price results.
 pub struct BestPriceResponse {
     pub results: Vec<BestPriceResult>,
@@ -41,84 +89,346 @@ pub struct BestPriceResponse {
 /// Fetch the best bid/ask for one or more symbols.
 ///
 /// `symbols` should be Robinhood crypto pairs like "BTC-USD".
-pub async fn get_best_price(rh: &Robinhood, symbols: Vec<&str>) -> Result<BestPriceResponse, reqwest::Error>{
-    let mut path = String::from("/api/v1/crypto/marketdata/best_bid_ask/");
-    if !symbols.is_empty() {
-        path.push('?');
-        for (i, sym) in symbols.iter().enumerate() {
-            if i > 0 {
-                path.push('&');
-            }
-            // Consider URL-escaping sym if needed
-            path.push_str("symbol=");
-            path.push_str(sym);
+pub async fn get_best_price(rh: &Robinhood, symbols: Vec<&str>) -> Result<BestPriceResponse>{
+    let path = path_with_query(
+        "/api/v1/crypto/marketdata/best_bid_ask/",
+        symbols.iter().map(|sym| ("symbol", *sym)),
+    );
+    let resp = rh.send(Method::GET, &path, "").await?;
+    decode_response(resp)
+}
+
+/// Fetch the best bid/ask for `symbols`, keyed by symbol.
+///
+/// A requested symbol that Robinhood doesn't return is simply absent from the
+/// map. If the response repeats a symbol, the last result wins.
+pub async fn get_best_price_map(rh: &Robinhood, symbols: Vec<&str>) -> Result<HashMap<String, BestPriceResult>>{
+    let resp = get_best_price(rh, symbols).await?;
+    Ok(resp.into_map())
+}
+
+/// Like `get_best_price`, but one unknown symbol doesn't fail the others.
+///
+/// If Robinhood rejects the whole request (400 or 404), each symbol is
+/// requested on its own and the rejected ones are dropped. Symbols without a
+/// quote either way are listed in `missing`.
+pub async fn get_best_price_partial(rh: &Robinhood, symbols: Vec<&str>) -> Result<PartialResult<Vec<BestPriceResult>>>{
+    fetch_partial(symbols, |quote: &BestPriceResult| quote.symbol.as_str(), |symbols| async move {
+        let symbols = symbols.iter().map(String::as_str).collect();
+        Ok(get_best_price(rh, symbols).await?.results)
+    }).await
+}
+
+/// How many chunk requests `get_best_price_chunked` keeps in flight at once.
+pub const BEST_PRICE_CONCURRENCY: usize = 4;
+
+#[derive(Debug)]
+/// A chunk of symbols whose best-price request failed.
+pub struct ChunkFailure {
+    pub symbols: Vec<String>,
+    pub error: RobinError,
+}
+
+#[derive(Debug, Default)]
+/// Merged outcome of `get_best_price_chunked`.
+pub struct ChunkedBestPrice {
+    /// Quotes from every chunk that succeeded, in no particular order.
+    pub results: Vec<BestPriceResult>,
+    /// Chunks that failed, with the symbols they covered.
+    pub failures: Vec<ChunkFailure>,
+}
+
+/// Fetch best bid/ask for any number of symbols, `chunk_size` symbols per
+/// request, with at most `BEST_PRICE_CONCURRENCY` requests in flight.
+///
+/// Keeps each request's query string short enough for the API. A failed
+/// chunk doesn't fail the call: its symbols are reported in `failures` and
+/// the other chunks' quotes are still returned. A `chunk_size` of zero is
+/// treated as one.
+pub async fn get_best_price_chunked(rh: &Robinhood, symbols: Vec<&str>, chunk_size: usize) -> ChunkedBestPrice {
+    let outcomes: Vec<_> = futures::stream::iter(symbols.chunks(chunk_size.max(1)))
+        .map(|chunk| async move { (chunk, get_best_price(rh, chunk.to_vec()).await) })
+        .buffer_unordered(BEST_PRICE_CONCURRENCY)
+        .collect()
+        .await;
+    let mut merged = ChunkedBestPrice::default();
+    for (chunk, outcome) in outcomes {
+        match outcome {
+            Ok(resp) => merged.results.extend(resp.results),
+            Err(error) => merged.failures.push(ChunkFailure {
+                symbols: chunk.iter().map(|s| s.to_string()).collect(),
+                error,
+            }),
         }
     }
-    let headers = rh.auth_headers(&path, "GET", "");
-    let client = Client::new();
-    let resp = client
-        .get(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .send()
-        .await?.json::<BestPriceResponse>().await?;
-    Ok(resp)
+    merged
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl BestPriceResponse {
+    /// Key the results by symbol; the last result wins on duplicates.
+    fn into_map(self) -> HashMap<String, BestPriceResult> {
+        self.results.into_iter().map(|r| (r.symbol.clone(), r)).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Which side of the book an estimated price is quoted for.
+///
+/// `Both` asks for bid and ask together; the results then carry both the
+/// `bid_inclusive_of_sell_spread` and `ask_inclusive_of_buy_spread` fields.
+pub enum PriceSide {
+    Bid,
+    Ask,
+    Both,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl PriceSide {
+    /// The wire value sent in the `side` query parameter.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PriceSide::Bid => "bid",
+            PriceSide::Ask => "ask",
+            PriceSide::Both => "both",
+            PriceSide::Unknown(s) => s,
+        }
+    }
+}
+
+impl std::str::FromStr for PriceSide {
+    type Err = RobinError;
+
+    /// Parse "bid", "ask" or "both", ignoring case. Anything else, including
+    /// order sides like "buy", is rejected.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "bid" => Ok(PriceSide::Bid),
+            "ask" => Ok(PriceSide::Ask),
+            "both" => Ok(PriceSide::Both),
+            _ => Err(RobinError::InvalidArgument(format!("price side must be bid, ask or both, got {s:?}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Estimated execution price for a hypothetical trade request.
 pub struct EstimatedPriceResult {
     pub symbol: String,
 
-    pub side: String,
+    pub side: PriceSide,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub price: Decimal,
 
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub quantity: Decimal,
 
-    #[serde(with = "rust_decimal::serde::float_option", default)]
+    #[serde(with = "crate::decimal::flexible_decimal::float_option", default)]
     pub bid_inclusive_of_sell_spread: Option<Decimal>,
 
-    #[serde(with = "rust_decimal::serde::float_option", default)]
+    #[serde(with = "crate::decimal::flexible_decimal::float_option", default)]
     pub sell_spread: Option<Decimal>,
 
-    #[serde(with = "rust_decimal::serde::float_option", default)]
+    #[serde(with = "crate::decimal::flexible_decimal::float_option", default)]
     pub ask_inclusive_of_buy_spread: Option<Decimal>,
 
-    #[serde(with = "rust_decimal::serde::float_option", default)]
+    #[serde(with = "crate::decimal::flexible_decimal::float_option", default)]
     pub buy_spread: Option<Decimal>,
 
-    pub timestamp: String,
+    #[serde(with = "crate::time::rfc3339")]
+    pub timestamp: Timestamp,
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
+impl EstimatedPriceResult {
+    /// Price a seller would receive: the bid including spread when present,
+    /// otherwise `price` for a bid-side quote.
+    pub(crate) fn bid(&self) -> Option<Decimal> {
+        match self.side {
+            PriceSide::Bid => self.bid_inclusive_of_sell_spread.or(Some(self.price)),
+            _ => self.bid_inclusive_of_sell_spread,
+        }
+    }
+
+    /// Price a buyer would pay: the ask including spread when present,
+    /// otherwise `price` for an ask-side quote.
+    pub(crate) fn ask(&self) -> Option<Decimal> {
+        match self.side {
+            PriceSide::Ask => self.ask_inclusive_of_buy_spread.or(Some(self.price)),
+            _ => self.ask_inclusive_of_buy_spread,
+        }
+    }
+
+    /// Cost of the spread for this quote's `quantity`, measured against the
+    /// mid `price`: what a buyer pays above mid on an `Ask` quote, or what a
+    /// seller gives up below mid on a `Bid` quote.
+    ///
+    /// `None` for `Both`/unknown sides or when the spread-inclusive price for
+    /// the side is absent.
+    pub fn implied_fee(&self) -> Option<Decimal> {
+        let per_unit = match self.side {
+            PriceSide::Ask => self.ask_inclusive_of_buy_spread? - self.price,
+            PriceSide::Bid => self.price - self.bid_inclusive_of_sell_spread?,
+            _ => return None,
+        };
+        Some(per_unit * self.quantity)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// What a trade of a given size costs, from `estimate_trade_cost`.
+pub struct TradeCost {
+    /// `quantity` valued at the mid price.
+    pub gross: Decimal,
+    /// Spread cost relative to mid; see `EstimatedPriceResult::implied_fee`.
+    pub fee: Decimal,
+    /// What a buy pays (`gross + fee`) or a sell receives (`gross - fee`).
+    pub net: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Response wrapper containing estimated price results.
 pub struct EstimatedPriceResponse {
     pub results: Vec<EstimatedPriceResult>,
 }
 
+impl EstimatedPriceResponse {
+    /// Round-trip spread (ask minus bid) for `quantity`.
+    ///
+    /// Pairs up the bid and ask quotes for that size, whether they came back as
+    /// a single `both` result or as separate `bid` and `ask` results. Returns
+    /// `None` if either side is missing for that quantity.
+    pub fn spread(&self, quantity: Decimal) -> Option<Decimal> {
+        let quotes = || self.results.iter().filter(|r| r.quantity == quantity);
+        let bid = quotes().find_map(EstimatedPriceResult::bid)?;
+        let ask = quotes().find_map(EstimatedPriceResult::ask)?;
+        Some(ask - bid)
+    }
+}
+
 
 /// Get an estimated execution price for a given symbol, side, and quantity.
 ///
-/// `side` is either "bid" or "ask"; `quantity` is the trade size.
-pub async fn get_estimated_price(rh: &Robinhood, symbol: &str, side: &str, quantity: Decimal) -> Result<EstimatedPriceResponse, reqwest::Error> {
-    let  path = format!("/api/v1/crypto/marketdata/estimated_price/?symbol={symbol}&side={side}&quantity={quantity}");
-    let headers = rh.auth_headers(&path, "GET", "");
-    let client = Client::new();
-    let resp = client
-        .get(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .send()
-        .await?.json::<EstimatedPriceResponse>().await?;
-    Ok(resp)
+/// `side` is `PriceSide::Bid`, `PriceSide::Ask` or `PriceSide::Both`;
+/// `quantity` is the trade size.
+pub async fn get_estimated_price(rh: &Robinhood, symbol: &str, side: PriceSide, quantity: Decimal) -> Result<EstimatedPriceResponse> {
+    get_estimated_prices(rh, symbol, side, vec![quantity]).await
+}
+
+/// Get estimated execution prices for several quantities in a single request.
+///
+/// Robinhood takes the sizes as one comma-separated `quantity` value and
+/// returns one result per quantity, which is handy for building a depth ladder.
+/// Quantities are written in plain fixed-point form without trailing zeros
+/// (never scientific notation), exactly as they are signed.
+/// A `PriceSide::Unknown` side is rejected with `RobinError::InvalidArgument`
+/// without sending anything.
+pub async fn get_estimated_prices(rh: &Robinhood, symbol: &str, side: PriceSide, quantities: Vec<Decimal>) -> Result<EstimatedPriceResponse> {
+    check_side(&side)?;
+    let quantity = quantities.iter().map(to_api_string).collect::<Vec<_>>().join(",");
+    let path = path_with_query(
+        "/api/v1/crypto/marketdata/estimated_price/",
+        [("symbol", symbol), ("side", side.as_str()), ("quantity", quantity.as_str())],
+    );
+    let resp = rh.send(Method::GET, &path, "").await?;
+    decode_response(resp)
+}
+
+/// Reject sides the API doesn't understand before a request is wasted on them.
+fn check_side(side: &PriceSide) -> Result<()> {
+    match side {
+        PriceSide::Unknown(s) => Err(RobinError::InvalidArgument(format!("price side must be bid, ask or both, got {s:?}"))),
+        _ => Ok(()),
+    }
+}
+
+/// Get bid and ask estimates for `quantity` in one call.
+///
+/// Use `EstimatedPriceResponse::spread` on the result to get the implied
+/// round-trip spread for that size.
+pub async fn get_estimated_price_both(rh: &Robinhood, symbol: &str, quantity: Decimal) -> Result<EstimatedPriceResponse> {
+    get_estimated_price(rh, symbol, PriceSide::Both, quantity).await
+}
+
+/// Estimate the gross value, spread cost and net amount of trading
+/// `quantity` of `symbol`.
+///
+/// `side` is `PriceSide::Ask` for a buy or `PriceSide::Bid` for a sell; any
+/// other side is rejected with `RobinError::InvalidArgument`. Returns
+/// `Ok(None)` if Robinhood's estimate lacks the spread-inclusive price.
+pub async fn estimate_trade_cost(rh: &Robinhood, symbol: &str, side: PriceSide, quantity: Decimal) -> Result<Option<TradeCost>> {
+    if !matches!(side, PriceSide::Ask | PriceSide::Bid) {
+        return Err(RobinError::InvalidArgument(format!("trade cost needs side ask (buy) or bid (sell), got {side:?}")));
+    }
+    let resp = get_estimated_price(rh, symbol, side.clone(), quantity).await?;
+    let Some(quote) = resp.results.iter().find(|r| r.side == side) else {
+        return Ok(None);
+    };
+    Ok(quote.implied_fee().map(|fee| {
+        let gross = quote.price * quote.quantity;
+        let net = if side == PriceSide::Ask { gross + fee } else { gross - fee };
+        TradeCost { gross, fee, net }
+    }))
+}
+
+/// Synchronous versions of the market data endpoints.
+///
+/// These use a blocking HTTP client and must not be called from inside an
+/// async runtime thread; use the async functions there instead.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use super::*;
+    use crate::error::decode_blocking_response;
+
+    /// Blocking version of [`super::get_best_price`].
+    pub fn get_best_price(rh: &Robinhood, symbols: Vec<&str>) -> Result<BestPriceResponse> {
+        let path = path_with_query(
+            "/api/v1/crypto/marketdata/best_bid_ask/",
+            symbols.iter().map(|sym| ("symbol", *sym)),
+        );
+        let resp = rh.send_blocking(Method::GET, &path, "")?;
+        decode_blocking_response(resp)
+    }
+
+    /// Blocking version of [`super::get_best_price_map`].
+    pub fn get_best_price_map(rh: &Robinhood, symbols: Vec<&str>) -> Result<HashMap<String, BestPriceResult>> {
+        Ok(get_best_price(rh, symbols)?.into_map())
+    }
+
+    /// Blocking version of [`super::get_estimated_price`].
+    pub fn get_estimated_price(rh: &Robinhood, symbol: &str, side: PriceSide, quantity: Decimal) -> Result<EstimatedPriceResponse> {
+        get_estimated_prices(rh, symbol, side, vec![quantity])
+    }
+
+    /// Blocking version of [`super::get_estimated_prices`].
+    pub fn get_estimated_prices(rh: &Robinhood, symbol: &str, side: PriceSide, quantities: Vec<Decimal>) -> Result<EstimatedPriceResponse> {
+        check_side(&side)?;
+        let quantity = quantities.iter().map(to_api_string).collect::<Vec<_>>().join(",");
+        let path = path_with_query(
+            "/api/v1/crypto/marketdata/estimated_price/",
+            [("symbol", symbol), ("side", side.as_str()), ("quantity", quantity.as_str())],
+        );
+        let resp = rh.send_blocking(Method::GET, &path, "")?;
+        decode_blocking_response(resp)
+    }
+
+    /// Blocking version of [`super::get_estimated_price_both`].
+    pub fn get_estimated_price_both(rh: &Robinhood, symbol: &str, quantity: Decimal) -> Result<EstimatedPriceResponse> {
+        get_estimated_price(rh, symbol, PriceSide::Both, quantity)
+    }
 }
 
 
 #[tokio::test]
 async fn test_best_price(){
-    let rh = Robinhood::from_env();
+    let rh = crate::auth::cassette_robinhood("best_price");
     match get_best_price(&rh, vec!["BTC-USD"]).await{
         Ok(resp) =>{
             assert_eq!(resp.results.len(), 1);
@@ -132,16 +442,285 @@ async fn test_best_price(){
 
 #[tokio::test]
 async fn test_estimated_price(){
-    let rh = Robinhood::from_env();
-    match get_estimated_price(&rh, "BTC-USD", "bid",Decimal::from(1)).await{
+    let rh = crate::auth::cassette_robinhood("estimated_price");
+    match get_estimated_price(&rh, "BTC-USD", PriceSide::Bid, Decimal::from(1)).await{
         Ok(resp) =>{
             assert_eq!(resp.results.len(), 1);
             assert_eq!(resp.results[0].symbol, "BTC-USD");
-            assert_eq!(resp.results[0].side, "bid");
+            assert_eq!(resp.results[0].side, PriceSide::Bid);
             assert_eq!(resp.results[0].quantity, Decimal::from(1));
         }
         Err(e) => {
             panic!("Error with estimated price: {}", e);
         }
     }
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_best_price_escaped_symbol_signature(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/best_bid_ask/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": []})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    get_best_price(&rh, vec!["BTC-USD", "BAD SYM&x=1"]).await.unwrap();
+    let req = &server.received_requests().await.unwrap()[0];
+    assert_eq!(req.url.query(), Some("symbol=BTC-USD&symbol=BAD+SYM%26x%3D1"));
+    crate::auth::assert_signed(&rh, req);
+}
+
+
+#[tokio::test]
+async fn test_estimated_prices_batches_quantities(){
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("quantity", "0.000001,1,250000,0.0000000000000000000000000001,79228162514264337593543950335"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": []})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let quantities = vec![Decimal::new(100, 8), Decimal::from(1), Decimal::new(25_000_000, 2), Decimal::new(1, 28), Decimal::MAX];
+    get_estimated_prices(&rh, "BTC-USD", PriceSide::Ask, quantities).await.unwrap();
+    let req = &server.received_requests().await.unwrap()[0];
+    let (_, quantity) = req.url.query_pairs().find(|(k, _)| k == "quantity").unwrap();
+    assert!(!quantity.contains(['e', 'E']));
+    crate::auth::assert_signed(&rh, req);
+}
+
+#[tokio::test]
+async fn test_estimated_price_both_spread(){
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("side", "both"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-USD",
+            "side": "both",
+            "price": 100.5,
+            "quantity": 1,
+            "bid_inclusive_of_sell_spread": 99.5,
+            "sell_spread": 0.005,
+            "ask_inclusive_of_buy_spread": 101.5,
+            "buy_spread": 0.005,
+            "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let resp = get_estimated_price_both(&rh, "BTC-USD", Decimal::from(1)).await.unwrap();
+    assert_eq!(resp.results[0].side, PriceSide::Both);
+    assert_eq!(resp.spread(Decimal::from(1)), Some(Decimal::from(2)));
+    assert_eq!(resp.spread(Decimal::from(2)), None);
+}
+
+#[test]
+fn test_spread_pairs_separate_sides(){
+    let quote = |side: &str, price: f64| serde_json::json!({
+        "symbol": "BTC-USD",
+        "side": side,
+        "price": price,
+        "quantity": 0.5,
+        "timestamp": "2025-01-01T00:00:00Z"
+    });
+    let resp: EstimatedPriceResponse = serde_json::from_value(serde_json::json!({
+        "results": [quote("ask", 101.25), quote("bid", 99.75)]
+    })).unwrap();
+    assert_eq!(resp.spread(Decimal::new(5, 1)), Some(Decimal::new(15, 1)));
+}
+
+#[test]
+fn test_best_price_spread_helpers(){
+    let quote = |bid: f64, ask: f64| -> BestPriceResult {
+        serde_json::from_value(serde_json::json!({
+            "symbol": "BTC-USD",
+            "price": 100.0,
+            "bid_inclusive_of_sell_spread": bid,
+            "sell_spread": 0.001,
+            "ask_inclusive_of_buy_spread": ask,
+            "buy_spread": 0.001,
+            "timestamp": "2025-01-01T00:00:00Z"
+        })).unwrap()
+    };
+
+    let normal = quote(99.0, 101.0);
+    assert_eq!(normal.mid_price(), Decimal::from(100));
+    assert_eq!(normal.spread(), Decimal::from(2));
+    assert_eq!(normal.spread_bps(), Decimal::from(200));
+
+    let crossed = quote(101.0, 99.0);
+    assert_eq!(crossed.spread(), Decimal::from(-2));
+    assert_eq!(crossed.spread_bps(), Decimal::from(-200));
+
+    assert_eq!(quote(0.0, 0.0).spread_bps(), Decimal::ZERO);
+    assert_eq!(normal.to_string(), "BTC-USD bid/ask 99/101 spread 2");
+}
+
+#[tokio::test]
+async fn test_get_best_price_map(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let quote = |symbol: &str, price: f64| serde_json::json!({
+        "symbol": symbol, "price": price,
+        "bid_inclusive_of_sell_spread": price, "sell_spread": 0.001,
+        "ask_inclusive_of_buy_spread": price, "buy_spread": 0.001,
+        "timestamp": "2025-01-01T00:00:00Z"
+    });
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [
+            quote("BTC-USD", 1.0), quote("ETH-USD", 2.0), quote("BTC-USD", 3.0)
+        ]})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let prices = get_best_price_map(&rh, vec!["BTC-USD", "ETH-USD", "NOPE-USD"]).await.unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices["BTC-USD"].price, Decimal::from(3));
+    assert_eq!(prices["ETH-USD"].price, Decimal::from(2));
+    assert!(!prices.contains_key("NOPE-USD"));
+}
+
+#[tokio::test]
+async fn test_price_side_is_normalized_and_unknowns_rejected(){
+    use wiremock::MockServer;
+
+    assert_eq!("Bid".parse::<PriceSide>().unwrap(), PriceSide::Bid);
+    assert_eq!("ASK".parse::<PriceSide>().unwrap(), PriceSide::Ask);
+    assert!(matches!("buy".parse::<PriceSide>(), Err(RobinError::InvalidArgument(_))));
+
+    let server = MockServer::start().await;
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let side = PriceSide::Unknown("BUY".to_string());
+    let err = get_estimated_price(&rh, "BTC-USD", side, Decimal::from(1)).await.unwrap_err();
+    assert!(matches!(err, RobinError::InvalidArgument(_)));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_best_price_chunked_reports_failed_chunks(){
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let quote = |symbol: &str| serde_json::json!({
+        "symbol": symbol, "price": 1.0,
+        "bid_inclusive_of_sell_spread": 1.0, "sell_spread": 0.001,
+        "ask_inclusive_of_buy_spread": 1.0, "buy_spread": 0.001,
+        "timestamp": "2025-01-01T00:00:00Z"
+    });
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("symbol", "C-USD"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    for (first, second) in [("A-USD", "B-USD"), ("E-USD", "F-USD")] {
+        Mock::given(method("GET"))
+            .and(query_param("symbol", first))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [quote(first), quote(second)]})))
+            .mount(&server)
+            .await;
+    }
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let symbols = vec!["A-USD", "B-USD", "C-USD", "D-USD", "E-USD", "F-USD"];
+    let merged = get_best_price_chunked(&rh, symbols, 2).await;
+    let mut ok: Vec<_> = merged.results.iter().map(|r| r.symbol.as_str()).collect();
+    ok.sort();
+    assert_eq!(ok, ["A-USD", "B-USD", "E-USD", "F-USD"]);
+    assert_eq!(merged.failures.len(), 1);
+    assert_eq!(merged.failures[0].symbols, ["C-USD", "D-USD"]);
+    assert!(matches!(merged.failures[0].error, RobinError::Api { status: 500, .. }));
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_get_best_price_partial_drops_rejected_symbols(){
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("symbol", "NOTACOIN"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("invalid symbol"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-USD", "price": 1.0,
+            "bid_inclusive_of_sell_spread": 1.0, "sell_spread": 0.001,
+            "ask_inclusive_of_buy_spread": 1.0, "buy_spread": 0.001,
+            "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let partial = get_best_price_partial(&rh, vec!["BTC-USD", "NOTACOIN"]).await.unwrap();
+    assert_eq!(partial.results.len(), 1);
+    assert_eq!(partial.results[0].symbol, "BTC-USD");
+    assert_eq!(partial.missing, vec!["NOTACOIN".to_string()]);
+    // the batch, then one request per symbol
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+    let alone = get_best_price_partial(&rh, vec!["NOTACOIN"]).await.unwrap();
+    assert!(alone.results.is_empty());
+    assert_eq!(alone.missing, vec!["NOTACOIN".to_string()]);
+}
+
+#[tokio::test]
+async fn test_implied_fee_and_trade_cost(){
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let quote = |side: &str| serde_json::json!({
+        "symbol": "BTC-USD", "side": side, "price": 100.0, "quantity": 2.0,
+        "bid_inclusive_of_sell_spread": 99.0, "sell_spread": 0.01,
+        "ask_inclusive_of_buy_spread": 101.5, "buy_spread": 0.015,
+        "timestamp": "2025-01-01T00:00:00Z"
+    });
+    let d = |s: &str| s.parse::<Decimal>().unwrap();
+    let ask: EstimatedPriceResult = serde_json::from_value(quote("ask")).unwrap();
+    assert_eq!(ask.implied_fee(), Some(d("3")));
+    let bid: EstimatedPriceResult = serde_json::from_value(quote("bid")).unwrap();
+    assert_eq!(bid.implied_fee(), Some(d("2")));
+    let both: EstimatedPriceResult = serde_json::from_value(quote("both")).unwrap();
+    assert_eq!(both.implied_fee(), None);
+    let mut bare = quote("ask");
+    bare["ask_inclusive_of_buy_spread"] = serde_json::Value::Null;
+    assert_eq!(serde_json::from_value::<EstimatedPriceResult>(bare).unwrap().implied_fee(), None);
+
+    let server = MockServer::start().await;
+    for side in ["ask", "bid"] {
+        Mock::given(method("GET"))
+            .and(query_param("side", side))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [quote(side)]})))
+            .mount(&server)
+            .await;
+    }
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let buy = estimate_trade_cost(&rh, "BTC-USD", PriceSide::Ask, d("2")).await.unwrap().unwrap();
+    assert_eq!((buy.gross, buy.fee, buy.net), (d("200"), d("3"), d("203")));
+    let sell = estimate_trade_cost(&rh, "BTC-USD", PriceSide::Bid, d("2")).await.unwrap().unwrap();
+    assert_eq!((sell.gross, sell.fee, sell.net), (d("200"), d("2"), d("198")));
+    assert!(matches!(
+        estimate_trade_cost(&rh, "BTC-USD", PriceSide::Both, d("2")).await,
+        Err(RobinError::InvalidArgument(_))
+    ));
+}
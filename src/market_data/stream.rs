@@ -0,0 +1,182 @@
+//! Continuous best bid/ask updates as a `Stream`.
+//!
+//! Robinhood's crypto trading API does not publish a WebSocket market-data
+//! feed, so the subscription polls `best_bid_ask` and yields only quotes whose
+//! timestamp changed since the last poll. Callers consume it like a socket
+//! feed; if a streaming endpoint becomes available it can back the same API.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use futures::stream::{self, Stream};
+use crate::auth::Robinhood;
+use crate::error::Result;
+use crate::backoff::Backoff;
+use crate::time::Timestamp;
+use tokio::time::MissedTickBehavior;
+use super::{get_best_price, BestPriceResponse, BestPriceResult};
+
+/// How often `subscribe_best_price` checks for new quotes.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Subscribe to best bid/ask updates for `symbols`, checking once a second.
+///
+/// See `subscribe_best_price_with_interval`.
+pub fn subscribe_best_price<'a>(rh: &'a Robinhood, symbols: Vec<&str>) -> impl Stream<Item = Result<BestPriceResult>> + 'a {
+    subscribe_best_price_with_interval(rh, symbols, DEFAULT_POLL_INTERVAL)
+}
+
+/// Subscribe to best bid/ask updates for `symbols`, checking every `interval`.
+///
+/// The first poll yields the current quote for every symbol; later polls
+/// yield a symbol only when its quote timestamp has moved. A failed poll is
+/// yielded as an `Err` and the subscription carries on for the same symbols
+/// after a jittered exponential backoff, so the stream only ends when dropped
+/// or when the client is shut down.
+pub fn subscribe_best_price_with_interval<'a>(rh: &'a Robinhood, symbols: Vec<&str>, interval: Duration) -> impl Stream<Item = Result<BestPriceResult>> + 'a {
+    let symbols: Vec<String> = symbols.into_iter().map(String::from).collect();
+    let backoff = Backoff::default();
+    let state = (VecDeque::new(), HashMap::<String, Timestamp>::new(), None::<u32>, true);
+    stream::unfold(state, move |(mut buffer, mut last_seen, mut failures, mut first)| {
+        let symbols = symbols.clone();
+        let backoff = failures.map(|attempt| backoff.delay(attempt));
+        async move {
+            loop {
+                if let Some(quote) = buffer.pop_front() {
+                    return Some((Ok(quote), (buffer, last_seen, failures, first)));
+                }
+                if rh.is_shutting_down() {
+                    return None;
+                }
+                let wait = match backoff {
+                    Some(delay) if failures.is_some() => delay,
+                    _ if first => Duration::ZERO,
+                    _ => interval,
+                };
+                tokio::time::sleep(wait).await;
+                first = false;
+
+                let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+                match get_best_price(rh, symbols).await {
+                    Ok(resp) => {
+                        failures = None;
+                        for quote in resp.results {
+                            if last_seen.get(&quote.symbol) != Some(&quote.timestamp) {
+                                last_seen.insert(quote.symbol.clone(), quote.timestamp.to_owned());
+                                buffer.push_back(quote);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        failures = Some(failures.map_or(0, |n| n.saturating_add(1)));
+                        return Some((Err(e), (buffer, last_seen, failures, first)));
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Fetch best bid/ask for `symbols` on every tick of `interval`, yielding
+/// each full response.
+///
+/// Unlike `subscribe_best_price`, every poll is yielded, unchanged quotes
+/// included, and a failed poll is yielded as an `Err` with no extra backoff
+/// before the next tick. Polls never overlap: a tick that passes while a
+/// request is still in flight (or while the consumer hasn't asked for the next
+/// item) is skipped rather than queued, so a slow response doesn't trigger a
+/// burst of catch-up requests. The first poll happens immediately. The
+/// stream ends when dropped or when the client is shut down.
+///
+/// Panics if `interval` is zero.
+pub fn poll_best_price<'a>(rh: &'a Robinhood, symbols: Vec<&str>, interval: Duration) -> impl Stream<Item = Result<BestPriceResponse>> + 'a {
+    let symbols: Vec<String> = symbols.into_iter().map(String::from).collect();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    stream::unfold(ticker, move |mut ticker| {
+        let symbols = symbols.clone();
+        async move {
+            ticker.tick().await;
+            if rh.is_shutting_down() {
+                return None;
+            }
+            let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+            Some((get_best_price(rh, symbols).await, ticker))
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_subscribe_best_price_recovers_and_skips_stale_quotes(){
+    use futures::StreamExt;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let quote = |ts: &str| serde_json::json!({
+        "symbol": "BTC-USD",
+        "price": 100.0,
+        "bid_inclusive_of_sell_spread": 99.0,
+        "sell_spread": 0.01,
+        "ask_inclusive_of_buy_spread": 101.0,
+        "buy_spread": 0.01,
+        "timestamp": ts
+    });
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [quote("2025-01-01T00:00:00Z")]})))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [quote("2025-01-01T00:00:01Z")]})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let updates = subscribe_best_price_with_interval(&rh, vec!["BTC-USD"], Duration::from_millis(10));
+    let updates: Vec<_> = updates.take(3).collect().await;
+    assert!(updates[0].is_err());
+    let first = updates[1].as_ref().unwrap();
+    let second = updates[2].as_ref().unwrap();
+    // the unchanged second poll is skipped
+    assert_ne!(first.timestamp, second.timestamp);
+    assert_eq!(server.received_requests().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_poll_best_price_yields_every_tick_without_overlap(){
+    use futures::StreamExt;
+    use std::time::Instant;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({"results": []}))
+            .set_delay(Duration::from_millis(60)))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let started = Instant::now();
+    let polls: Vec<_> = poll_best_price(&rh, vec!["BTC-USD"], Duration::from_millis(10)).take(4).collect().await;
+    assert!(polls[0].is_err());
+    assert!(polls[1..].iter().all(|poll| poll.as_ref().is_ok_and(|resp| resp.results.is_empty())));
+    // the ticks missed during each slow response were skipped, not replayed
+    assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    assert!(started.elapsed() >= Duration::from_millis(180));
+
+    rh.shutdown().await;
+    let mut after = std::pin::pin!(poll_best_price(&rh, vec!["BTC-USD"], Duration::from_millis(10)));
+    assert!(after.next().await.is_none());
+}
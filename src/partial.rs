@@ -0,0 +1,78 @@
+//! Multi-symbol requests that tolerate individual bad symbols.
+//!
+//! Robinhood either rejects a whole multi-symbol request because of one
+//! unknown symbol, or silently leaves that symbol out. The `*_partial`
+//! helpers turn both into the same outcome: the results that could be
+//! fetched plus the requested symbols that came back empty.
+
+use std::collections::HashSet;
+use std::future::Future;
+use futures::StreamExt;
+use crate::error::{RobinError, Result};
+
+/// How many single-symbol requests a `*_partial` helper keeps in flight when
+/// it has to fall back to asking for each symbol on its own.
+pub const PARTIAL_FALLBACK_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Results for the symbols Robinhood returned, plus the ones it didn't.
+pub struct PartialResult<T> {
+    pub results: T,
+    /// Requested symbols with no result, in request order and without duplicates.
+    pub missing: Vec<String>,
+}
+
+impl<T> PartialResult<T> {
+    /// Whether every requested symbol came back.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Whether `err` is Robinhood refusing the symbols themselves rather than
+/// the request failing for some other reason.
+fn is_bad_symbol(err: &RobinError) -> bool {
+    matches!(err, RobinError::Api { status: 400 | 404, .. } | RobinError::NotFound(_))
+}
+
+/// Fetch `symbols` in one request; if Robinhood rejects it as a bad request,
+/// retry each symbol alone so only the bad ones are dropped. A rejected
+/// single symbol is simply reported missing.
+///
+/// `key` reads the symbol a result belongs to. Errors other than a rejected
+/// symbol (network, auth, 5xx, ...) still fail the whole call.
+pub(crate) async fn fetch_partial<T, F, Fut>(symbols: Vec<&str>, key: impl Fn(&T) -> &str, fetch: F) -> Result<PartialResult<Vec<T>>>
+where
+    F: Fn(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let requested: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+    let results = match fetch(requested.clone()).await {
+        Err(e) if requested.len() == 1 && is_bad_symbol(&e) => Vec::new(),
+        Err(e) if requested.len() > 1 && is_bad_symbol(&e) => {
+            let outcomes: Vec<_> = futures::stream::iter(requested.iter().cloned())
+                .map(|symbol| fetch(vec![symbol]))
+                .buffered(PARTIAL_FALLBACK_CONCURRENCY)
+                .collect()
+                .await;
+            let mut results = Vec::new();
+            for outcome in outcomes {
+                match outcome {
+                    Ok(found) => results.extend(found),
+                    Err(e) if is_bad_symbol(&e) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            results
+        }
+        other => other?,
+    };
+
+    let returned: HashSet<&str> = results.iter().map(&key).collect();
+    let mut seen = HashSet::new();
+    let missing = requested.iter()
+        .filter(|s| !returned.contains(s.as_str()) && seen.insert(s.as_str()))
+        .cloned()
+        .collect();
+    Ok(PartialResult { results, missing })
+}
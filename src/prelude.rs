@@ -0,0 +1,51 @@
+//! The types most programs need, in one import.
+//!
+//! `use robinrust::prelude::*;` brings in the client and its configuration,
+//! the order parameter builders,
+//! the request and response types, the error type and `Decimal`, plus the
+//! everyday endpoint functions. Anything more specialised is still imported
+//! from its module.
+
+pub use rust_decimal::Decimal;
+
+pub use crate::auth::{ClientConfig, Environment, Robinhood};
+pub use crate::error::{ApiError, Result, RobinError};
+pub use crate::retry::RetryConfig;
+pub use crate::symbol::Symbol;
+
+pub use crate::account::{get_account_info, get_portfolio_value, AccountInfo, AccountStatus, Currency, PortfolioValue};
+pub use crate::market_data::{
+    get_best_price, get_estimated_price, BestPriceResponse, BestPriceResult, EstimatedPriceResponse,
+    EstimatedPriceResult, PriceSide,
+};
+pub use crate::trading::{
+    cancel_crypto_order, create_crypto_order, get_crypto_holdings, get_crypto_order, get_crypto_orders,
+    limit_buy, limit_sell, market_buy, market_sell, new_client_order_id, CancelOrderResponse,
+    CreateCryptoOrderResponse, CreateCyptoOrderParams, CryptoHoldings, CryptoHoldingsResponse, CryptoOrder,
+    CryptoOrdersResponse, Cursor, GetCryptoOrderParams, LimitOrderConfig, MarketOrderConfig, OrderConfig,
+    OrderSide, OrderState, OrderType, PageParams, StopLimitOrderConfig, StopLossOrderConfig, TimeInForce,
+    TradingPairs,
+};
+
+#[test]
+fn test_prelude_glob_import_is_unambiguous() {
+    use crate::prelude::*;
+
+    let params = GetCryptoOrderParams::builder().side(OrderSide::Buy).build();
+    assert_eq!(params.side, Some(OrderSide::Buy));
+    let amount: Result<Decimal> = Ok(Decimal::ONE);
+    assert!(matches!(amount, Ok(d) if d == Decimal::ONE));
+    assert_eq!(Currency::Usd.as_str(), "USD");
+}
+
+#[test]
+fn test_prelude_keeps_two_argument_result() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::prelude::*;
+
+    fn parse(s: &str) -> Result<Decimal, rust_decimal::Error> {
+        s.parse()
+    }
+    assert_eq!(parse("1.5")?, Decimal::new(15, 1));
+    let _: Result<(), RobinError> = Ok(());
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Query-string helpers shared by the endpoint modules.
+//!
+//! The path passed to `auth_headers` must be byte-for-byte identical to the
+//! path reqwest sends, otherwise the signature check fails with a 401. Every
+//! query string is therefore URL-encoded here exactly once and the result is
+//! used both for signing and for the request URL.
+
+use serde::Serialize;
+
+/// Append URL-encoded `key=value` pairs to `path`.
+///
+/// Returns `path` unchanged when `pairs` is empty.
+pub(crate) fn path_with_query<'a>(path: &str, pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish();
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{query}")
+    }
+}
+
+/// Append a serializable parameter struct to `path` as a URL-encoded query.
+///
+/// `None` fields are skipped by the structs' `skip_serializing_if`, so an
+/// empty parameter set yields `path` unchanged.
+pub(crate) fn path_with_params<T: Serialize>(path: &str, params: &T) -> String {
+    let query = serde_urlencoded::to_string(params)
+        .expect("query parameter structs only hold strings, numbers and unit enums");
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{query}")
+    }
+}
+
+#[test]
+fn test_path_with_query_escapes_reserved_characters() {
+    let path = path_with_query("/x/", [("symbol", "BTC-USD"), ("symbol", "A B&C=D")]);
+    assert_eq!(path, "/x/?symbol=BTC-USD&symbol=A+B%26C%3DD");
+    assert_eq!(path_with_query("/x/", []), "/x/");
+}
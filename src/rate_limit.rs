@@ -0,0 +1,117 @@
+//! Rate limiting: the limits reported by Robinhood on each response, and an
+//! optional client-side token bucket that keeps requests under a fixed rate.
+//!
+//! The following response headers are parsed; if Robinhood renames them,
+//! only the constants below need to change:
+//! - `x-ratelimit-limit`: requests allowed in the current window
+//! - `x-ratelimit-remaining`: requests left in the current window
+//! - `x-ratelimit-reset`: seconds until the window resets
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use reqwest::header::HeaderMap;
+use tokio::sync::Mutex;
+use crate::auth::Robinhood;
+
+/// Header carrying the request allowance for the current window.
+pub const LIMIT_HEADER: &str = "x-ratelimit-limit";
+/// Header carrying the requests left in the current window.
+pub const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Header carrying the seconds until the window resets.
+pub const RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Snapshot of the rate-limit headers from the most recent response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub remaining: u32,
+    pub limit: u32,
+    pub reset: SystemTime,
+}
+
+impl RateLimit {
+    /// Parse the rate-limit headers, returning `None` if any is missing or malformed.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let num = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+        Some(Self {
+            remaining: u32::try_from(num(REMAINING_HEADER)?).ok()?,
+            limit: u32::try_from(num(LIMIT_HEADER)?).ok()?,
+            reset: SystemTime::now() + Duration::from_secs(num(RESET_HEADER)?),
+        })
+    }
+}
+
+/// Client-side token bucket shared by every request made through a client.
+///
+/// The bucket holds up to `rps` tokens and refills at `rps` tokens per second.
+/// Each request takes one token; when the bucket is empty the request waits
+/// until a token is available instead of failing. Waiters are served in order.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    rps: f64,
+    state: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `rps` requests per second (minimum 1).
+    pub fn new(rps: u32) -> Self {
+        let rps = f64::from(rps.max(1));
+        Self {
+            rps,
+            state: Arc::new(Mutex::new(Bucket { tokens: rps, last_refill: Instant::now() })),
+        }
+    }
+
+    /// Wait until a token is available and take it.
+    pub async fn acquire(&self) {
+        let mut bucket = self.state.lock().await;
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rps).min(self.rps);
+        bucket.last_refill = now;
+        if bucket.tokens < 1.0 {
+            // hold the lock while sleeping so later callers queue behind us
+            let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / self.rps);
+            tokio::time::sleep(wait).await;
+            bucket.tokens = 1.0;
+            bucket.last_refill = Instant::now();
+        }
+        bucket.tokens -= 1.0;
+    }
+}
+
+impl Robinhood {
+    /// Rate-limit values reported by the most recent response, if any were sent.
+    ///
+    /// Updated after every response, so concurrent tasks sharing this client
+    /// all see the latest values.
+    pub fn last_rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    /// Store the rate-limit headers from a response, keeping the previous
+    /// snapshot if the response didn't include them.
+    pub(crate) fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(rl) = RateLimit::from_headers(headers) {
+            *self.rate_limit.lock().unwrap() = Some(rl);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limiter_spaces_out_requests() {
+    let limiter = RateLimiter::new(10);
+    let start = Instant::now();
+    for _ in 0..20 {
+        limiter.acquire().await;
+    }
+    // 10 tokens are available up front, the other 10 refill at 10/s
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(900), "finished too fast: {elapsed:?}");
+    assert!(elapsed < Duration::from_millis(2000), "finished too slow: {elapsed:?}");
+}
@@ -0,0 +1,106 @@
+//! Several Robinhood accounts behind one connection pool.
+//!
+//! `AccountRegistry` maps a label such as `"main"` to a `Robinhood` client so
+//! calls can be routed per account. Every registered client shares the
+//! registry's `reqwest::Client`, while keys, retries and rate limits stay
+//! per account.
+
+use std::collections::HashMap;
+use reqwest::Client;
+use crate::auth::{ClientConfig, Robinhood};
+use crate::error::Result;
+
+/// Labelled `Robinhood` clients sharing one HTTP connection pool.
+pub struct AccountRegistry {
+    client: Client,
+    accounts: HashMap<String, Robinhood>,
+}
+
+impl AccountRegistry {
+    /// An empty registry with a fresh shared HTTP client.
+    ///
+    /// Fails with `RobinError::Http` if the HTTP client can't be built.
+    pub fn new() -> Result<Self> {
+        Ok(AccountRegistry { client: ClientConfig::default().build_client()?, accounts: HashMap::new() })
+    }
+
+    /// Register credentials under `label`, replacing any account already there.
+    ///
+    /// Returns `RobinError::Auth` if the private key is malformed.
+    pub fn add(&mut self, label: impl Into<String>, api_key: String, signing_priv_b64: String, public_key: String) -> Result<&Robinhood> {
+        let rh = Robinhood::new(api_key, signing_priv_b64, public_key)?;
+        Ok(self.insert(label, rh))
+    }
+
+    /// Register an already configured client under `label`.
+    ///
+    /// Its HTTP client is replaced by the registry's shared one, so connection
+    /// settings such as `with_client_config` are not carried over; other
+    /// settings (base URL, retries, rate limit, hooks) are kept.
+    pub fn insert(&mut self, label: impl Into<String>, mut rh: Robinhood) -> &Robinhood {
+        rh.client = self.client.clone();
+        let label = label.into();
+        self.accounts.insert(label.clone(), rh);
+        &self.accounts[&label]
+    }
+
+    /// The client registered under `label`, if any.
+    pub fn get(&self, label: &str) -> Option<&Robinhood> {
+        self.accounts.get(label)
+    }
+
+    /// Remove and return the client registered under `label`.
+    pub fn remove(&mut self, label: &str) -> Option<Robinhood> {
+        self.accounts.remove(label)
+    }
+
+    /// Labels of every registered account, in no particular order.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+#[tokio::test]
+async fn test_registry_routes_per_account(){
+    use base64::{engine::general_purpose::STANDARD as b64, Engine as _};
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    for (key, number) in [("rh-api-main", "1"), ("rh-api-alt", "2")] {
+        Mock::given(method("GET"))
+            .and(header("x-api-key", key))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "account_number": number,
+                "status": "active",
+                "buying_power": "1.00",
+                "buying_power_currency": "USD"
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    let mut registry = AccountRegistry::new().unwrap();
+    for (label, key, seed) in [("main", "rh-api-main", 1u8), ("alt", "rh-api-alt", 2u8)] {
+        let rh = Robinhood::new(key.to_string(), b64.encode([seed; 32]), "pub".to_string()).unwrap()
+            .with_base_url(server.uri());
+        registry.insert(label, rh);
+    }
+    registry.add("spare", "rh-api-spare".to_string(), b64.encode([3u8; 32]), "pub".to_string()).unwrap();
+    assert!(registry.add("bad", "k".to_string(), "short".to_string(), "p".to_string()).is_err());
+    assert_eq!(registry.len(), 3);
+
+    let main = registry.get("main").unwrap();
+    assert_eq!(crate::account::get_account_info(main).await.unwrap().account_number, "1");
+    let alt = registry.get("alt").unwrap();
+    assert_eq!(crate::account::get_account_info(alt).await.unwrap().account_number, "2");
+    assert!(registry.get("missing").is_none());
+}
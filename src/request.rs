@@ -0,0 +1,299 @@
+//! The single request path shared by every endpoint helper.
+//!
+//! Signing, base URL handling, retries and the `on_request`/`on_response`
+//! hooks live here so each endpoint only has to build its path and decode
+//! the response. `get_json`/`post_json` expose the same path for raw JSON.
+
+use std::time::Instant;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::Method;
+use crate::auth::Robinhood;
+use serde_json::Value;
+use crate::error::{decode_response, RobinError, Result};
+use crate::retry::{is_retryable_status, retry_after};
+use crate::transport::{request_id, HttpResponse, ReqwestTransport, SignedRequest, Transport};
+
+impl Robinhood {
+    /// Sign a request for `path` (including any query string) without sending it.
+    ///
+    /// `path` starts with `/api/...` and must already be URL-encoded, since
+    /// it is signed as given; `body` is the exact JSON to send, or empty.
+    /// Each call takes a fresh timestamp, so the signature is only valid for
+    /// the server's acceptance window.
+    pub fn signed_request(&self, method: &Method, path: &str, body: &str) -> Result<SignedRequest> {
+        let mut headers = self.headers.clone();
+        headers.extend(self.auth_headers(path, method.as_str(), body)?);
+        if !body.is_empty() {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+        Ok(SignedRequest {
+            method: method.clone(),
+            url: format!("{}{path}", self.base_url),
+            headers,
+            body: (!body.is_empty()).then(|| body.to_string()),
+            timeout: self.timeout,
+        })
+    }
+
+    /// Sign a request the way every built-in call does and return it as a
+    /// `reqwest::RequestBuilder` on this client's connection pool.
+    ///
+    /// For endpoints the crate doesn't wrap yet: add nothing that changes the
+    /// path or body, then `.send()` it promptly. Unlike `get_json`, the
+    /// request bypasses retries, the rate limiter, hooks and any custom
+    /// `Transport`, and the response is not checked or recorded.
+    pub fn signed_request_builder(&self, method: Method, path: &str, body: &str) -> Result<reqwest::RequestBuilder> {
+        Ok(self.signed_request(&method, path, body)?.into_reqwest(&self.client))
+    }
+
+    /// Sign and send a request for `path` (including any query string).
+    ///
+    /// `body` is sent verbatim as JSON when non-empty, so the signed body is
+    /// exactly the transmitted body. GET requests are retried according to
+    /// `self.retry`; other methods are sent once. Every attempt first waits
+    /// on the client-side rate limiter, if one is configured, and is delivered
+    /// through the configured `Transport`. After `shutdown` no new request or
+    /// retry is started.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, body), fields(%method)))]
+    pub(crate) async fn send(&self, method: Method, path: &str, body: &str) -> Result<HttpResponse> {
+        let _in_flight = self.shutdown.enter()?;
+        let mut attempt = 0;
+        loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire().await;
+            }
+            if attempt > 0 && self.is_shutting_down() {
+                return Err(RobinError::ShuttingDown);
+            }
+            let req = self.signed_request(&method, path, body)?;
+            #[cfg(feature = "tracing")]
+            tracing::debug!(attempt, "sending request");
+            if let Some(hook) = &self.request_hook {
+                hook(&method, path);
+            }
+            let started = Instant::now();
+            let result = match &self.transport {
+                Some(transport) => transport.send(req).await,
+                None => ReqwestTransport::new(self.client.clone()).send(req).await,
+            };
+            if let Ok(resp) = &result {
+                if let Some(hook) = &self.response_hook {
+                    hook(resp.status, started.elapsed());
+                }
+                self.record_rate_limit(&resp.headers);
+                self.record_request_id(&resp.headers);
+                #[cfg(feature = "tracing")]
+                log_status(resp.status);
+            }
+
+            let retry = match &self.retry {
+                Some(retry) if method == Method::GET && attempt < retry.max_retries => retry,
+                _ => return result,
+            };
+            let delay = match &result {
+                Ok(resp) if is_retryable_status(resp.status) => retry.delay(attempt, retry_after(&resp.headers)),
+                Err(RobinError::Timeout(_)) => retry.delay(attempt, None),
+                Err(RobinError::Http(e)) if e.is_connect() => retry.delay(attempt, None),
+                _ => return result,
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Signed GET of any API path, returning the raw JSON.
+    ///
+    /// An escape hatch for fields or endpoints the typed helpers don't model
+    /// yet. `path` starts with `/api/...` and must already be URL-encoded,
+    /// including any query string, since it is signed as given. Retries, rate
+    /// limiting and hooks apply as for every other call.
+    pub async fn get_json(&self, path: &str) -> Result<Value> {
+        let resp = self.send(Method::GET, path, "").await?;
+        decode_response(resp)
+    }
+
+    /// Signed POST of `body` to any API path, returning the raw JSON response.
+    ///
+    /// Like `get_json`, but for writes; the request is sent once, never retried.
+    /// A dry-run client refuses with `RobinError::InvalidArgument` rather than
+    /// send a write it cannot fake a response for.
+    pub async fn post_json(&self, path: &str, body: &Value) -> Result<Value> {
+        if self.dry_run {
+            return Err(RobinError::InvalidArgument(format!("post_json to {path} is not sent in dry-run mode")));
+        }
+        let body = serde_json::to_string(body)?;
+        let resp = self.send(Method::POST, path, &body).await?;
+        decode_response(resp)
+    }
+
+    /// `x-request-id` of the most recent response, for quoting in support tickets.
+    ///
+    /// Failed calls carry their own id in `RobinError::Api`; use this after a
+    /// successful call. Like `last_rate_limit`, it is shared by clones, so read
+    /// it right after the call when other tasks use the same client.
+    pub fn last_request_id(&self) -> Option<String> {
+        self.last_request_id.lock().unwrap().clone()
+    }
+
+    /// Store the request id of a response, clearing it when the response had none.
+    fn record_request_id(&self, headers: &HeaderMap) {
+        *self.last_request_id.lock().unwrap() = request_id(headers).map(str::to_string);
+    }
+
+    /// Blocking counterpart of `send`, used by the `blocking` endpoint mirrors.
+    ///
+    /// Signing and retries behave the same, but the client-side rate limiter is
+    /// async-only and is not applied here. The blocking HTTP client is built on
+    /// first use so async-only callers never create one.
+    #[cfg(feature = "blocking")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self, body), fields(%method)))]
+    pub(crate) fn send_blocking(&self, method: Method, path: &str, body: &str) -> Result<reqwest::blocking::Response> {
+        let _in_flight = self.shutdown.enter()?;
+        let client = match self.blocking_client.get() {
+            Some(client) => client,
+            None => {
+                let client = self.client_config.build_blocking_client()?;
+                self.blocking_client.get_or_init(|| client)
+            }
+        };
+        let mut attempt = 0;
+        loop {
+            if attempt > 0 && self.is_shutting_down() {
+                return Err(RobinError::ShuttingDown);
+            }
+            let signed = self.signed_request(&method, path, body)?;
+            let mut req = client
+                .request(signed.method, signed.url)
+                .timeout(signed.timeout)
+                .headers(signed.headers);
+            if let Some(body) = signed.body {
+                req = req.body(body);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(attempt, "sending request");
+            if let Some(hook) = &self.request_hook {
+                hook(&method, path);
+            }
+            let started = Instant::now();
+            let result = req.send();
+            if let Ok(resp) = &result {
+                if let Some(hook) = &self.response_hook {
+                    hook(resp.status(), started.elapsed());
+                }
+                self.record_rate_limit(resp.headers());
+                self.record_request_id(resp.headers());
+                #[cfg(feature = "tracing")]
+                log_status(resp.status());
+            }
+
+            let retry = match &self.retry {
+                Some(retry) if method == Method::GET && attempt < retry.max_retries => retry,
+                _ => return Ok(result?),
+            };
+            let delay = match &result {
+                Ok(resp) if is_retryable_status(resp.status()) => retry.delay(attempt, retry_after(resp.headers())),
+                Err(e) if e.is_connect() || e.is_timeout() => retry.delay(attempt, None),
+                _ => return Ok(result?),
+            };
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+/// Warn about non-2xx responses; the body is left for the caller to report.
+#[cfg(feature = "tracing")]
+fn log_status(status: reqwest::StatusCode) {
+    if status.is_success() {
+        tracing::debug!(%status, "response received");
+    } else {
+        tracing::warn!(%status, "request failed");
+    }
+}
+
+#[tokio::test]
+async fn test_raw_json_keeps_unmodelled_fields() {
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"account_number": "1", "new_field": [1, 2]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(body_json(serde_json::json!({"symbol": "BTC-USD"})))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "o1"})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let account = rh.get_json("/api/v1/crypto/trading/accounts/").await.unwrap();
+    assert_eq!(account["new_field"], serde_json::json!([1, 2]));
+    let order = rh.post_json("/api/v1/crypto/trading/orders/", &serde_json::json!({"symbol": "BTC-USD"})).await.unwrap();
+    assert_eq!(order["id"], "o1");
+    for req in server.received_requests().await.unwrap() {
+        crate::auth::assert_signed(&rh, &req);
+    }
+
+    let dry = rh.with_dry_run(true);
+    let err = dry.post_json("/api/v1/crypto/trading/orders/", &serde_json::json!({})).await.unwrap_err();
+    assert!(matches!(err, RobinError::InvalidArgument(_)));
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_request_id_is_captured() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ok/"))
+        .respond_with(ResponseTemplate::new(200).insert_header("x-request-id", "req-ok").set_body_json(serde_json::json!({})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/bad/"))
+        .respond_with(ResponseTemplate::new(400).insert_header("x-request-id", "req-bad").set_body_string("nope"))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    assert_eq!(rh.last_request_id(), None);
+    rh.get_json("/ok/").await.unwrap();
+    assert_eq!(rh.last_request_id().as_deref(), Some("req-ok"));
+    match rh.get_json("/bad/").await {
+        Err(RobinError::Api { status: 400, request_id, .. }) => assert_eq!(request_id.as_deref(), Some("req-bad")),
+        other => panic!("expected api error, got {other:?}"),
+    }
+    assert_eq!(rh.last_request_id().as_deref(), Some("req-bad"));
+}
+
+#[tokio::test]
+async fn test_signed_request_builder_matches_built_in_signing() {
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/crypto/new_endpoint/"))
+        .and(query_param("symbol", "BTC-USD"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let resp = rh.signed_request_builder(Method::POST, "/api/v2/crypto/new_endpoint/?symbol=BTC-USD", "{\"a\":1}")
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let req = &server.received_requests().await.unwrap()[0];
+    assert_eq!(req.body, b"{\"a\":1}");
+    crate::auth::assert_signed(&rh, req);
+}
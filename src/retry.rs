@@ -0,0 +1,74 @@
+//! Retry policy for transient API failures.
+//!
+//! Only idempotent GET requests are retried. Order creation and cancellation
+//! are never retried automatically since a duplicate POST could place or
+//! cancel twice.
+
+use std::time::Duration;
+use crate::backoff::Backoff;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+/// How many times, and how far apart, to retry a failed GET request.
+///
+/// A request is retried on HTTP 429, any 5xx status, or a connection/timeout
+/// error. Delays grow exponentially from `base_delay` and are capped at
+/// `max_delay`, with random jitter so concurrent clients don't retry in lockstep.
+/// A `Retry-After` header (in seconds) on the response takes precedence.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before retry number `attempt` (starting at 0).
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(wait) = retry_after {
+            return wait;
+        }
+        self.backoff().delay(attempt)
+    }
+
+    /// The backoff schedule between retries, ignoring `Retry-After`.
+    pub fn backoff(&self) -> Backoff {
+        Backoff::new(self.base_delay, self.max_delay)
+    }
+}
+
+/// Whether a response status is worth retrying.
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parse a `Retry-After` header given in whole seconds.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+#[test]
+fn test_retry_delay_is_capped_and_jittered() {
+    let cfg = RetryConfig {
+        max_retries: 5,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(300),
+    };
+    for attempt in 0..5 {
+        let expected = (Duration::from_millis(100) * 2u32.pow(attempt)).min(cfg.max_delay);
+        let delay = cfg.delay(attempt, None);
+        assert!(delay >= expected / 2 && delay <= expected, "attempt {attempt}: {delay:?}");
+    }
+    assert_eq!(cfg.delay(0, Some(Duration::from_secs(7))), Duration::from_secs(7));
+}
@@ -0,0 +1,113 @@
+//! Graceful shutdown: refuse new requests and wait for in-flight ones.
+//!
+//! Every request holds an `InFlight` guard from the first attempt until its
+//! response (or final error) is in hand, so `Robinhood::shutdown` can wait for
+//! exactly those and nothing else.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tokio::sync::Notify;
+use crate::auth::Robinhood;
+use crate::error::{RobinError, Result};
+
+/// Shutdown flag and in-flight request count, shared by a client's clones.
+#[derive(Default)]
+pub(crate) struct Shutdown {
+    closing: AtomicBool,
+    in_flight: AtomicUsize,
+    drained: Notify,
+}
+
+/// Marks one request as in flight until dropped.
+pub(crate) struct InFlight<'a>(&'a Shutdown);
+
+impl Shutdown {
+    /// Register a new request, or refuse it with `RobinError::ShuttingDown`.
+    pub(crate) fn enter(&self) -> Result<InFlight<'_>> {
+        // count first, then check, so `wait` never misses a request that got in
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlight(self);
+        if self.closing.load(Ordering::SeqCst) {
+            return Err(RobinError::ShuttingDown);
+        }
+        Ok(guard)
+    }
+
+    fn begin(&self) {
+        self.closing.store(true, Ordering::SeqCst);
+    }
+
+    fn is_closing(&self) -> bool {
+        self.closing.load(Ordering::SeqCst)
+    }
+
+    /// Resolve once no request is in flight.
+    async fn wait(&self) {
+        loop {
+            let drained = self.drained.notified();
+            tokio::pin!(drained);
+            drained.as_mut().enable();
+            if self.in_flight.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            drained.await;
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.drained.notify_waiters();
+        }
+    }
+}
+
+impl Robinhood {
+    /// Stop accepting requests and wait for the ones already in flight.
+    ///
+    /// Once called, every request on this client and its clones fails
+    /// immediately with `RobinError::ShuttingDown`, including retries and
+    /// rate-limit waits that have not been sent yet. Requests already sent are
+    /// left to finish, so an order submitted just before the call still gets
+    /// its response. Best-price subscriptions end after their buffered quotes.
+    /// Shutdown can't be undone; build a new client to resume.
+    pub async fn shutdown(&self) {
+        self.shutdown.begin();
+        self.shutdown.wait().await;
+    }
+
+    /// Whether `shutdown` has been called on this client or one of its clones.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.is_closing()
+    }
+}
+
+#[tokio::test]
+async fn test_shutdown_drains_in_flight_requests() {
+    use std::time::Duration;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({"ok": true}))
+            .set_delay(Duration::from_millis(200)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let worker = rh.clone();
+    let slow = tokio::spawn(async move { worker.get_json("/api/v1/slow/").await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    rh.shutdown().await;
+    assert!(slow.is_finished(), "shutdown returned before the in-flight request completed");
+    assert_eq!(slow.await.unwrap().unwrap()["ok"], true);
+    assert!(rh.is_shutting_down());
+    assert!(matches!(rh.get_json("/api/v1/later/").await, Err(RobinError::ShuttingDown)));
+
+    // nothing in flight: returns straight away
+    tokio::time::timeout(Duration::from_millis(100), rh.shutdown()).await.unwrap();
+}
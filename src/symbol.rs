@@ -0,0 +1,151 @@
+//! Pre-flight validation for crypto pair symbols.
+//!
+//! Robinhood answers a malformed symbol such as "BTCUSD" with an empty result
+//! or a generic error after a full round trip. `Symbol::parse` catches those
+//! mistakes locally; `Symbol` derefs to `str`, so a parsed symbol can be passed
+//! anywhere the endpoint helpers take `&str`.
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+/// A validated `ASSET-QUOTE` pair such as "BTC-USD".
+///
+/// Both parts are non-empty and made of uppercase ASCII letters and digits,
+/// joined by a single hyphen.
+pub struct Symbol {
+    symbol: String,
+    hyphen: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a string is not a valid `ASSET-QUOTE` symbol.
+pub enum ParseSymbolError {
+    /// The symbol does not contain exactly one hyphen.
+    Separator(String),
+    /// The asset or quote part is empty.
+    EmptyPart(String),
+    /// The symbol contains something other than uppercase letters, digits and the hyphen.
+    InvalidCharacter { symbol: String, ch: char },
+}
+
+impl fmt::Display for ParseSymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseSymbolError::Separator(s) => {
+                write!(f, "symbol {s:?} must be ASSET-QUOTE with a single hyphen, e.g. \"BTC-USD\"")
+            }
+            ParseSymbolError::EmptyPart(s) => write!(f, "symbol {s:?} has an empty asset or quote"),
+            ParseSymbolError::InvalidCharacter { symbol, ch } => {
+                write!(f, "symbol {symbol:?} contains {ch:?}; only uppercase letters and digits are allowed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseSymbolError {}
+
+impl Symbol {
+    /// Validate `s` as an `ASSET-QUOTE` pair.
+    pub fn parse(s: &str) -> Result<Self, ParseSymbolError> {
+        if let Some(ch) = s.chars().find(|c| *c != '-' && !c.is_ascii_uppercase() && !c.is_ascii_digit()) {
+            return Err(ParseSymbolError::InvalidCharacter { symbol: s.to_string(), ch });
+        }
+        let hyphen = match s.match_indices('-').map(|(i, _)| i).collect::<Vec<_>>()[..] {
+            [i] => i,
+            _ => return Err(ParseSymbolError::Separator(s.to_string())),
+        };
+        if hyphen == 0 || hyphen == s.len() - 1 {
+            return Err(ParseSymbolError::EmptyPart(s.to_string()));
+        }
+        Ok(Self { symbol: s.to_string(), hyphen })
+    }
+
+    /// The asset code, e.g. "BTC".
+    pub fn asset(&self) -> &str {
+        &self.symbol[..self.hyphen]
+    }
+
+    /// The quote currency, e.g. "USD".
+    pub fn quote(&self) -> &str {
+        &self.symbol[self.hyphen + 1..]
+    }
+
+    /// The full symbol, e.g. "BTC-USD".
+    pub fn as_str(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl FromStr for Symbol {
+    type Err = ParseSymbolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Symbol {
+    type Error = ParseSymbolError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Symbol {
+    type Error = ParseSymbolError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.symbol
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.symbol)
+    }
+}
+
+#[test]
+fn test_symbol_parse() {
+    let btc: Symbol = "BTC-USD".parse().unwrap();
+    assert_eq!((btc.asset(), btc.quote(), btc.as_str()), ("BTC", "USD", "BTC-USD"));
+    assert_eq!(Symbol::try_from("1INCH-USD").unwrap().asset(), "1INCH");
+
+    assert_eq!(Symbol::parse("BTCUSD"), Err(ParseSymbolError::Separator("BTCUSD".into())));
+    assert_eq!(Symbol::parse("BTC-USD-X"), Err(ParseSymbolError::Separator("BTC-USD-X".into())));
+    assert_eq!(Symbol::parse("-USD"), Err(ParseSymbolError::EmptyPart("-USD".into())));
+    assert_eq!(Symbol::parse("BTC-"), Err(ParseSymbolError::EmptyPart("BTC-".into())));
+    assert_eq!(
+        Symbol::parse("btc-usd"),
+        Err(ParseSymbolError::InvalidCharacter { symbol: "btc-usd".into(), ch: 'b' })
+    );
+    assert!(Symbol::parse("BTC USD").is_err());
+
+    assert_eq!(serde_json::to_string(&btc).unwrap(), "\"BTC-USD\"");
+    assert!(serde_json::from_str::<Symbol>("\"BTCUSD\"").is_err());
+}
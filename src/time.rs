@@ -0,0 +1,175 @@
+//! Timestamp type used by response structs, and clock-skew correction.
+//!
+//! With the `chrono` feature enabled, `Timestamp` is a `chrono::DateTime<Utc>`
+//! parsed from the RFC 3339 strings Robinhood returns (with or without
+//! fractional seconds). Without it, timestamps are kept as the raw `String`.
+//!
+//! `sync_time` measures how far the local clock is from Robinhood's so signed
+//! `x-timestamp` values stay within the server's accepted window.
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime};
+use reqwest::header::DATE;
+use reqwest::Method;
+use crate::auth::Robinhood;
+use crate::error::{RobinError, Result};
+
+/// Clock offsets larger than this are likely to get requests rejected, and
+/// are logged as a warning by `sync_time` when the `tracing` feature is on.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// Measure the offset between the local clock and Robinhood's and store it on
+/// `rh`, so later request timestamps are corrected by it.
+///
+/// Sends one signed request and reads the server's `Date` header; the
+/// response status doesn't matter, so this also works when the current skew
+/// is causing 401s. Returns the offset in seconds (positive when the local
+/// clock is behind).
+pub async fn sync_time(rh: &Robinhood) -> Result<i64> {
+    let resp = rh.send(Method::GET, "/api/v1/crypto/trading/accounts/", "").await?;
+    let received_at = SystemTime::now();
+    let server_time = resp.headers.get(DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| httpdate::parse_http_date(date).ok())
+        .ok_or_else(|| RobinError::Auth("server response has no valid Date header".to_string()))?;
+    let offset = match server_time.duration_since(received_at) {
+        Ok(ahead) => ahead.as_secs() as i64,
+        Err(behind) => -(behind.duration().as_secs() as i64),
+    };
+    #[cfg(feature = "tracing")]
+    if offset.unsigned_abs() > MAX_CLOCK_SKEW.as_secs() {
+        tracing::warn!(offset, "local clock differs from server time by more than MAX_CLOCK_SKEW");
+    }
+    rh.clock_offset.store(offset, Ordering::Relaxed);
+    Ok(offset)
+}
+
+/// A point in time as returned by the API.
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+
+/// A point in time as returned by the API.
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = String;
+
+/// The current time as a `Timestamp`, for values the crate makes up itself
+/// (e.g. dry-run orders). Without `chrono` it is RFC 3339 with whole seconds.
+pub(crate) fn now() -> Timestamp {
+    #[cfg(feature = "chrono")]
+    return SystemTime::now().into();
+    #[cfg(not(feature = "chrono"))]
+    rfc3339_seconds(SystemTime::now())
+}
+
+/// `t` as RFC 3339 in UTC with whole seconds, the form the `*_at_start` and
+/// `*_at_end` order filters take. Times before 1970 become the epoch.
+pub(crate) fn rfc3339_seconds(t: SystemTime) -> String {
+    let secs = t.duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    // civil-from-days (Howard Hinnant), valid for any date after 1970
+    let z = days + 719_468;
+    let (era, doe) = (z / 146_097, z % 146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z", rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Serde adapter for `Timestamp` fields.
+///
+/// Parses any RFC 3339 offset and normalizes to UTC. Serializes as RFC 3339
+/// with a `Z` suffix and the fewest of 0, 3, 6 or 9 fractional digits that
+/// hold the value. The instant always survives a round trip, but the text
+/// only does in that form: `.5Z` comes back as `.500Z` and `+00:00` as `Z`.
+#[cfg(feature = "chrono")]
+pub(crate) mod rfc3339 {
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ts: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&ts.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<DateTime<Utc>, D::Error> {
+        let raw = String::deserialize(d)?;
+        DateTime::parse_from_rfc3339(&raw)
+            .map(|ts| ts.with_timezone(&Utc))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serde adapter for `Timestamp` fields; passes the raw string through.
+#[cfg(not(feature = "chrono"))]
+pub(crate) mod rfc3339 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ts: &str, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(ts)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+        String::deserialize(d)
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_timestamp_parses_with_and_without_fraction() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Wrapper {
+        #[serde(with = "rfc3339")]
+        ts: Timestamp,
+    }
+
+    for raw in ["2024-03-26T20:33:15Z", "2024-03-26T20:33:15.461427Z", "2024-03-26T20:33:15.120Z"] {
+        let json = format!("{{\"ts\":\"{raw}\"}}");
+        let w: Wrapper = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&w).unwrap(), json);
+    }
+
+    // offsets are normalized to UTC
+    let w: Wrapper = serde_json::from_str("{\"ts\":\"2024-03-26T20:33:15.461427-04:00\"}").unwrap();
+    assert_eq!(serde_json::to_string(&w).unwrap(), "{\"ts\":\"2024-03-27T00:33:15.461427Z\"}");
+
+    // other precisions keep their value but are padded to the next of 3, 6 or 9 digits
+    for (raw, written) in [("2024-03-26T20:33:15.5Z", "2024-03-26T20:33:15.500Z"), ("2024-03-26T20:33:15.12345+00:00", "2024-03-26T20:33:15.123450Z")] {
+        let w: Wrapper = serde_json::from_str(&format!("{{\"ts\":\"{raw}\"}}")).unwrap();
+        assert_eq!(serde_json::to_string(&w).unwrap(), format!("{{\"ts\":\"{written}\"}}"));
+        let again: Wrapper = serde_json::from_str(&serde_json::to_string(&w).unwrap()).unwrap();
+        assert_eq!(again.ts, w.ts);
+    }
+}
+
+#[test]
+fn test_rfc3339_seconds() {
+    assert_eq!(rfc3339_seconds(SystemTime::UNIX_EPOCH), "1970-01-01T00:00:00Z");
+    let leap_day = SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+    assert_eq!(rfc3339_seconds(leap_day), "2024-02-29T12:34:56Z");
+}
+
+#[tokio::test]
+async fn test_sync_time_corrects_request_timestamps(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server_now = SystemTime::now() + Duration::from_secs(300);
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401).insert_header("date", httpdate::fmt_http_date(server_now)))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let offset = sync_time(&rh).await.unwrap();
+    assert!((298..=300).contains(&offset), "offset {offset}");
+    assert_eq!(rh.clock_offset(), offset);
+
+    sync_time(&rh).await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    let ts: u64 = requests[1].headers.get("x-timestamp").unwrap().to_str().unwrap().parse().unwrap();
+    let expected = server_now.duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    assert!(ts.abs_diff(expected) <= 2, "timestamp {ts} vs server {expected}");
+}
@@ -2,74 +2,504 @@
 //!
 //! This module exposes helpers to query trading pairs and holdings, list and
 //! create crypto orders, and cancel existing orders. All functions rely on
-//! authenticated requests built via the `auth` module.
+//! authenticated requests built via the `auth` module. See `stream` for
+//! order state changes as they happen.
 
 use crate::auth::Robinhood;
-use reqwest::Client;
-use rust_decimal::Decimal;
+use crate::time::Timestamp;
+use crate::error::{decode_response, RobinError, Result};
+use crate::market_data::{get_best_price_map, get_estimated_price, PriceSide};
+use crate::partial::{fetch_partial, PartialResult};
+use crate::query::{path_with_params, path_with_query};
+use crate::transport::{HttpResponse, SignedRequest};
+use reqwest::Method;
+use futures::stream::{Stream, StreamExt, TryStreamExt};
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime};
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
-#[derive(Debug, Serialize, Deserialize)]
+
+pub mod stream;
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Response containing available crypto trading pairs.
 pub struct CryptoTradingPairsResponse{
-    pub next: Option<String>,
-    pub previous: Option<String>,
+    #[serde(default, with = "cursor_url")]
+    pub next: Option<Cursor>,
+    #[serde(default, with = "cursor_url")]
+    pub previous: Option<Cursor>,
     pub results: Vec<TradingPairs>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// A tradable crypto pair (e.g., BTC-USD) with increments and size limits.
+///
+/// Hashes by `symbol` only, so sets and maps of pairs are keyed by symbol.
 pub struct TradingPairs{
     pub asset_code: String,
     pub quote_code: String,
-    pub quote_increment: String,
-    pub asset_increment: String,
-    pub max_order_size: String,
-    pub min_order_size: String,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub quote_increment: Decimal,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub asset_increment: Decimal,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub max_order_size: Decimal,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub min_order_size: Decimal,
     pub status: String,
     pub symbol: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why an order would be rejected by a pair's size and increment rules.
+pub enum ValidationError {
+    /// Quantity is smaller than the pair's `min_order_size`.
+    BelowMinimum { quantity: Decimal, min: Decimal },
+    /// Quantity is larger than the pair's `max_order_size`.
+    AboveMaximum { quantity: Decimal, max: Decimal },
+    /// Quantity is not a multiple of the pair's `asset_increment`.
+    QuantityIncrement { quantity: Decimal, increment: Decimal },
+    /// Price is not a multiple of the pair's `quote_increment`.
+    PriceIncrement { price: Decimal, increment: Decimal },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::BelowMinimum { quantity, min } => {
+                write!(f, "quantity {quantity} is below the minimum order size {min}")
+            }
+            ValidationError::AboveMaximum { quantity, max } => {
+                write!(f, "quantity {quantity} is above the maximum order size {max}")
+            }
+            ValidationError::QuantityIncrement { quantity, increment } => {
+                write!(f, "quantity {quantity} is not a multiple of the asset increment {increment}")
+            }
+            ValidationError::PriceIncrement { price, increment } => {
+                write!(f, "price {price} is not a multiple of the quote increment {increment}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Whether `value` is an exact multiple of `increment` (a zero increment allows anything).
+fn is_multiple_of(value: Decimal, increment: Decimal) -> bool {
+    increment.is_zero() || (value % increment).is_zero()
+}
+
+impl Hash for TradingPairs {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state);
+    }
+}
+
 impl TradingPairs{
     /// Check if a quantity is within the allowed min/max order sizes for this pair.
+    ///
+    /// This only checks size; `asset_increment` is the step between valid
+    /// quantities, not the minimum. Use `validate_order` to check both.
     pub fn check_valid_trade(&self, quantity: Decimal) -> bool{
-        let max_order_size = Decimal::from_str(&self.max_order_size).unwrap();
-        let min_order_size = Decimal::from_str(&self.min_order_size).unwrap();
-        quantity <= max_order_size && quantity >= min_order_size
+        quantity <= self.max_order_size && quantity >= self.min_order_size
+    }
+
+    /// Check an order against this pair's rules before submitting it.
+    ///
+    /// Verifies `quantity` is within the min/max order size and a multiple of
+    /// `asset_increment`, and that `price` (if given) is a multiple of
+    /// `quote_increment`.
+    pub fn validate_order(&self, quantity: Decimal, price: Option<Decimal>) -> std::result::Result<(), ValidationError> {
+        let (min, max) = (self.min_order_size, self.max_order_size);
+        let (asset_increment, quote_increment) = (self.asset_increment, self.quote_increment);
+        if quantity < min {
+            return Err(ValidationError::BelowMinimum { quantity, min });
+        }
+        if quantity > max {
+            return Err(ValidationError::AboveMaximum { quantity, max });
+        }
+        if !is_multiple_of(quantity, asset_increment) {
+            return Err(ValidationError::QuantityIncrement { quantity, increment: asset_increment });
+        }
+        if let Some(price) = price
+            && !is_multiple_of(price, quote_increment)
+        {
+            return Err(ValidationError::PriceIncrement { price, increment: quote_increment });
+        }
+        Ok(())
+    }
+
+    /// Snap `quantity` down to the nearest multiple of `asset_increment`.
+    #[deprecated(note = "use `round_quantity`, which rounds the same way and keeps the increment's scale")]
+    pub fn round_to_increment(&self, quantity: Decimal) -> Decimal {
+        self.round_quantity(quantity)
+    }
+
+    /// Round `price` half-up to the nearest multiple of `quote_increment`, at
+    /// the increment's scale, e.g. `101.455` -> `101.46` for a `0.01` step.
+    /// Suited to displaying prices; for an order price, check the direction
+    /// you need (a buy limit rounded up pays more).
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        round_to_multiple(price, self.quote_increment, RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    /// Round `quantity` down to a multiple of `asset_increment`, at the
+    /// increment's scale, so an order never asks for more than was meant.
+    /// With an increment of `1` the result is a whole number.
+    pub fn round_quantity(&self, quantity: Decimal) -> Decimal {
+        round_to_multiple(quantity, self.asset_increment, RoundingStrategy::ToZero)
+    }
+}
+
+/// Round `value` to a multiple of `increment` using `strategy`, expressed with
+/// as many decimal places as the increment has (trailing zeros ignored).
+/// A non-positive increment leaves `value` unchanged.
+fn round_to_multiple(value: Decimal, increment: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if increment <= Decimal::ZERO {
+        return value;
+    }
+    let mut rounded = (value / increment).round_dp_with_strategy(0, strategy) * increment;
+    rounded.rescale(increment.normalize().scale());
+    rounded
+}
+
+#[cfg(test)]
+fn test_pair() -> TradingPairs {
+    TradingPairs {
+        asset_code: "BTC".to_string(),
+        quote_code: "USD".to_string(),
+        quote_increment: "0.01".parse::<Decimal>().unwrap(),
+        asset_increment: "0.0001".parse::<Decimal>().unwrap(),
+        max_order_size: Decimal::from(20),
+        min_order_size: "0.0005".parse::<Decimal>().unwrap(),
+        status: "tradable".to_string(),
+        symbol: "BTC-USD".to_string(),
+    }
+}
+
+#[test]
+fn test_check_valid_trade_uses_min_order_size(){
+    // increment (0.0001) differs from the minimum (0.0005)
+    let pair = test_pair();
+    let d = |s: &str| s.parse::<Decimal>().unwrap();
+    assert!(!pair.check_valid_trade(d("0.0001")));
+    assert!(!pair.check_valid_trade(d("0.0004")));
+    assert!(pair.check_valid_trade(d("0.0005")));
+    assert!(pair.check_valid_trade(d("20")));
+    assert!(!pair.check_valid_trade(d("20.0001")));
+
+    // malformed sizes now fail at decode time instead of panicking later
+    let malformed = serde_json::json!({
+        "asset_code": "BTC", "quote_code": "USD", "quote_increment": "0.01",
+        "asset_increment": "0.0001", "max_order_size": "20", "min_order_size": "not-a-number",
+        "status": "tradable", "symbol": "BTC-USD"
+    });
+    assert!(serde_json::from_value::<TradingPairs>(malformed).is_err());
+}
+
+#[test]
+fn test_validate_order(){
+    let pair = test_pair();
+    let d = |s: &str| s.parse::<Decimal>().unwrap();
+    assert_eq!(pair.validate_order(d("0.0012"), Some(d("65000.25"))), Ok(()));
+    assert!(matches!(pair.validate_order(d("0.0001"), None), Err(ValidationError::BelowMinimum { .. })));
+    assert!(matches!(pair.validate_order(d("21"), None), Err(ValidationError::AboveMaximum { .. })));
+    assert!(matches!(pair.validate_order(d("0.00123"), None), Err(ValidationError::QuantityIncrement { .. })));
+    assert!(matches!(pair.validate_order(d("0.001"), Some(d("1.005"))), Err(ValidationError::PriceIncrement { .. })));
+    #[allow(deprecated)]
+    let snapped = pair.round_to_increment(d("0.00123456"));
+    assert_eq!(snapped, d("0.0012"));
+    assert_eq!(snapped.to_string(), pair.round_quantity(d("0.00123456")).to_string());
+}
+
+#[test]
+fn test_round_price_and_quantity(){
+    let d = |s: &str| s.parse::<Decimal>().unwrap();
+    let btc = test_pair();
+    // prices round half-up for display, at the increment's scale
+    assert_eq!(btc.round_price(d("65000.245")).to_string(), "65000.25");
+    assert_eq!(btc.round_price(d("65000.2449")).to_string(), "65000.24");
+    assert_eq!(btc.round_price(d("65000")).to_string(), "65000.00");
+    // quantities always round down
+    assert_eq!(btc.round_quantity(d("0.00129999")).to_string(), "0.0012");
+    assert_eq!(btc.round_quantity(d("0.00005")).to_string(), "0.0000");
+
+    // sub-cent quote step and a whole-units-only asset, with API-style trailing zeros
+    let doge = TradingPairs {
+        quote_increment: d("0.000001000000000000"),
+        asset_increment: d("1.000000000000000000"),
+        ..test_pair()
+    };
+    assert_eq!(doge.round_price(d("0.1598125")).to_string(), "0.159813");
+    assert_eq!(doge.round_price(d("0.1598124999")).to_string(), "0.159812");
+    assert_eq!(doge.round_quantity(d("1234.9999")).to_string(), "1234");
+    assert_eq!(doge.round_quantity(d("0.99")).to_string(), "0");
+
+    // a step that isn't a power of ten still snaps to its multiples
+    let odd = TradingPairs { quote_increment: d("0.25"), asset_increment: d("0.5"), ..test_pair() };
+    assert_eq!(odd.round_price(d("10.125")), d("10.25"));
+    assert_eq!(odd.round_price(d("10.12")), d("10"));
+    assert_eq!(odd.round_quantity(d("2.99")), d("2.5"));
+
+    let unbounded = TradingPairs { quote_increment: Decimal::ZERO, ..test_pair() };
+    assert_eq!(unbounded.round_price(d("1.23456")), d("1.23456"));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+/// Position in a paginated listing, as found in a response's `next` or
+/// `previous` field.
+///
+/// Robinhood sends those as full URLs; this holds just their `cursor` query
+/// parameter, ready to pass back as `PageParams::cursor` or
+/// `GetCryptoOrderParams::cursor`.
+pub struct Cursor(String);
+
+impl Cursor {
+    /// Wrap a raw cursor value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Cursor(value.into())
+    }
+
+    /// Extract the `cursor` query parameter from a pagination URL, absolute
+    /// or relative. `None` if the URL has no (or an empty) cursor.
+    pub fn from_url(url: &str) -> Option<Cursor> {
+        let (_, query) = url.split_once('?')?;
+        let query = query.split('#').next().unwrap_or_default();
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(k, v)| k == "cursor" && !v.is_empty())
+            .map(|(_, v)| Cursor(v.into_owned()))
+    }
+
+    /// The raw cursor value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Cursor {
+    fn from(value: String) -> Self {
+        Cursor(value)
+    }
+}
+
+impl From<&str> for Cursor {
+    fn from(value: &str) -> Self {
+        Cursor(value.to_string())
+    }
+}
+
+impl std::fmt::Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Serde adapter for the `next`/`previous` URLs in list responses.
+///
+/// Reads the URL down to its `Cursor`, and writes it back as a relative
+/// `?cursor=...` URL so a serialized response deserializes to the same value.
+mod cursor_url {
+    use super::Cursor;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(cursor: &Option<Cursor>, s: S) -> Result<S::Ok, S::Error> {
+        cursor.as_ref()
+            .map(|c| crate::query::path_with_query("", [("cursor", c.as_str())]))
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Cursor>, D::Error> {
+        Ok(Option::<String>::deserialize(d)?.as_deref().and_then(Cursor::from_url))
+    }
+}
+
+#[test]
+fn test_cursor_from_url(){
+    let next = "https://trading.robinhood.com/api/v1/crypto/trading/orders/?cursor=cD0yMDI0%3D&limit=5";
+    assert_eq!(Cursor::from_url(next), Some(Cursor::new("cD0yMDI0=")));
+    assert_eq!(Cursor::from_url("/api/v1/crypto/trading/orders/?limit=5&cursor=abc#top"), Some(Cursor::from("abc")));
+    assert_eq!(Cursor::from_url("https://trading.robinhood.com/api/v1/crypto/trading/orders/?limit=5"), None);
+    assert_eq!(Cursor::from_url("https://trading.robinhood.com/api/v1/crypto/trading/orders/?cursor="), None);
+    assert_eq!(Cursor::from_url("not a url"), None);
+
+    let page: CryptoOrdersResponse = serde_json::from_value(serde_json::json!({
+        "next": next, "previous": null, "results": []
+    })).unwrap();
+    assert_eq!(page.next.as_ref().map(Cursor::as_str), Some("cD0yMDI0="));
+    assert_eq!(page.previous, None);
+    let round_trip: CryptoOrdersResponse = serde_json::from_str(&serde_json::to_string(&page).unwrap()).unwrap();
+    assert_eq!(round_trip, page);
+
+    let params = GetCryptoOrderParams::builder().cursor(page.next.unwrap()).limit(5u32).build();
+    assert_eq!(crate::query::path_with_params("/orders/", &params), "/orders/?cursor=cD0yMDI0%3D&limit=5");
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+/// Page selection for list endpoints that take symbol filters.
+///
+/// `cursor` is taken from a previous response's `next`; `limit` is the
+/// page size. Both default to the server's behaviour (first page, default size).
+pub struct PageParams{
+    #[builder(default, setter(strip_option, into))]
+    pub cursor: Option<Cursor>,
+    #[builder(default, setter(strip_option, into))]
+    pub limit: Option<u32>,
+}
+
+/// Build `path` with one `key=value` pair per symbol plus the page parameters
+/// and, for account-scoped endpoints, the account number.
+fn paged_path(path: &str, key: &str, symbols: &[&str], page: &PageParams, account_number: Option<&str>) -> String {
+    let limit = page.limit.map(|limit| limit.to_string());
+    path_with_query(
+        path,
+        symbols.iter().map(|sym| (key, *sym))
+            .chain(page.cursor.as_ref().map(|cursor| ("cursor", cursor.as_str())))
+            .chain(limit.as_deref().map(|limit| ("limit", limit)))
+            .chain(account_number.map(|account| ("account_number", account))),
+    )
+}
+
+/// Fetch pages via `fetch_page` until `next` runs out, collecting every result.
+///
+/// A cursor that was already visited stops pagination rather than looping.
+async fn collect_pages<T, F, Fut>(mut page: PageParams, mut fetch_page: F) -> Result<Vec<T>>
+where
+    F: FnMut(PageParams) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<Cursor>)>>,
+{
+    let mut items = Vec::new();
+    let mut seen = HashSet::new();
+    loop {
+        let (results, next) = fetch_page(page.clone()).await?;
+        items.extend(results);
+        match next {
+            Some(cursor) if seen.insert(cursor.clone()) => page.cursor = Some(cursor),
+            _ => return Ok(items),
+        }
     }
 }
 
 /// List supported crypto trading pairs, optionally filtered by symbol(s).
 ///
 /// `symbols` should be values like "BTC-USD"; when empty, returns all pairs.
-pub async fn get_crypto_trading_pairs(rh: &Robinhood, symbols: Vec<&str>) -> Result<CryptoTradingPairsResponse, reqwest::Error>{
-    let mut path = String::from("/api/v1/crypto/trading/trading_pairs/");
-    if !symbols.is_empty() {
-        path.push('?');
-        for (i, sym) in symbols.iter().enumerate() {
-            if i > 0 {
-                path.push('&');
-            }
-            // Consider URL-escaping sym if needed
-            path.push_str("symbol=");
-            path.push_str(sym);
+/// Only the first page is returned; see `get_crypto_trading_pairs_page` and
+/// `get_all_crypto_trading_pairs`.
+pub async fn get_crypto_trading_pairs(rh: &Robinhood, symbols: Vec<&str>) -> Result<CryptoTradingPairsResponse>{
+    get_crypto_trading_pairs_page(rh, symbols, PageParams::default()).await
+}
+
+/// Every trading pair for `symbols`, with the symbols Robinhood doesn't know
+/// listed in `missing` instead of failing the whole request.
+///
+/// Follows every page. See `get_best_price_partial` for how a rejected batch
+/// is retried symbol by symbol.
+pub async fn get_crypto_trading_pairs_partial(rh: &Robinhood, symbols: Vec<&str>) -> Result<PartialResult<Vec<TradingPairs>>>{
+    fetch_partial(symbols, |pair: &TradingPairs| pair.symbol.as_str(), |symbols| async move {
+        let symbols = symbols.iter().map(String::as_str).collect();
+        get_all_crypto_trading_pairs(rh, symbols, PageParams::default()).await
+    }).await
+}
+
+/// Fetch one page of trading pairs selected by `page`.
+pub async fn get_crypto_trading_pairs_page(rh: &Robinhood, symbols: Vec<&str>, page: PageParams) -> Result<CryptoTradingPairsResponse>{
+    let path = paged_path("/api/v1/crypto/trading/trading_pairs/", "symbol", &symbols, &page, None);
+    let resp = rh.send(Method::GET, &path, "").await?;
+    decode_response(resp)
+}
+
+/// List every trading pair matching `symbols`, following `next` cursors until
+/// the last page. `page.limit` is kept as the page size.
+pub async fn get_all_crypto_trading_pairs(rh: &Robinhood, symbols: Vec<&str>, page: PageParams) -> Result<Vec<TradingPairs>>{
+    collect_pages(page, |page| {
+        let symbols = symbols.clone();
+        async move {
+            let resp = get_crypto_trading_pairs_page(rh, symbols, page).await?;
+            Ok((resp.results, resp.next))
+        }
+    }).await
+}
+
+/// Fetch one trading pair, e.g. "BTC-USD".
+///
+/// Returns `None` if Robinhood doesn't list the pair or its `status` isn't
+/// `tradable`.
+pub async fn get_crypto_trading_pair(rh: &Robinhood, symbol: &str) -> Result<Option<TradingPairs>>{
+    let resp = get_crypto_trading_pairs(rh, vec![symbol]).await?;
+    Ok(resp.results.into_iter().find(|pair| pair.symbol == symbol && pair.status == "tradable"))
+}
+
+/// How long `Robinhood::trading_pair` reuses a fetched pair by default.
+pub const DEFAULT_PAIR_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Trading-pair lookups keyed by symbol, each stamped with when it was fetched.
+///
+/// Misses (unknown or untradable pairs) are cached too, so a bad symbol does
+/// not cost a request per order.
+pub(crate) struct PairCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<String, (Instant, Option<TradingPairs>)>>,
+}
+
+impl PairCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: RwLock::default() }
+    }
+
+    /// The cached lookup for `symbol`, if one exists and has not expired.
+    fn get(&self, symbol: &str) -> Option<Option<TradingPairs>> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(symbol)
+            .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+            .map(|(_, pair)| pair.clone())
+    }
+
+    fn insert(&self, symbol: &str, pair: Option<TradingPairs>) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        self.entries.write().unwrap().insert(symbol.to_string(), (Instant::now(), pair));
+    }
+
+    fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+}
+
+impl Robinhood {
+    /// Look up a tradable pair, reusing a cached copy younger than the
+    /// configured TTL (see `with_pair_cache_ttl`).
+    ///
+    /// Expired or missing entries are refetched with `get_crypto_trading_pair`.
+    /// The cache is shared by every task using this client, so order
+    /// validation can call this per order without a round-trip each time.
+    pub async fn trading_pair(&self, symbol: &str) -> Result<Option<TradingPairs>> {
+        if let Some(pair) = self.pair_cache.get(symbol) {
+            return Ok(pair);
         }
+        let pair = get_crypto_trading_pair(self, symbol).await?;
+        self.pair_cache.insert(symbol, pair.clone());
+        Ok(pair)
+    }
+
+    /// Drop every cached trading pair so the next `trading_pair` call refetches.
+    pub fn clear_pair_cache(&self) {
+        self.pair_cache.clear();
     }
-    let headers = rh.auth_headers(&path, "GET", "");
-    let client = Client::new();
-    let resp = client
-        .get(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .send()
-        .await?.json::<CryptoTradingPairsResponse>().await?;
-    Ok(resp)
 }
 
 #[tokio::test]
 async fn test_get_trading_pairs(){
-    let rh = Robinhood::from_env();
+    let rh = crate::auth::cassette_robinhood("trading_pairs");
     match get_crypto_trading_pairs(&rh, vec!["BTC-USD"]).await{
         Ok(resp) => {
             assert_eq!(resp.results[0].asset_code, "BTC");
@@ -81,54 +511,143 @@ async fn test_get_trading_pairs(){
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Response containing crypto holdings for the account.
 pub struct CryptoHoldingsResponse{
-    pub next: Option<String>,
-    pub previous: Option<String>,
+    #[serde(default, with = "cursor_url")]
+    pub next: Option<Cursor>,
+    #[serde(default, with = "cursor_url")]
+    pub previous: Option<Cursor>,
     pub results: Vec<CryptoHoldings>
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// A single crypto holding entry for the account.
 pub struct CryptoHoldings{
     pub account_number: String,
     pub asset_code: String,
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub total_quantity: Decimal,
-    #[serde(with = "rust_decimal::serde::float")]
+    #[serde(with = "crate::decimal::flexible_decimal::float")]
     pub quantity_available_for_trading: Decimal,
 }
 
+impl CryptoHoldings {
+    /// Quantity tied up in open orders: total minus what is available to trade.
+    ///
+    /// Clamped to zero should the API ever report more available than held.
+    pub fn locked_quantity(&self) -> Decimal {
+        (self.total_quantity - self.quantity_available_for_trading).max(Decimal::ZERO)
+    }
+}
+
+impl CryptoHoldingsResponse {
+    /// The holding for `asset_code` (e.g. "BTC") on this page, if any.
+    pub fn find(&self, asset_code: &str) -> Option<&CryptoHoldings> {
+        self.results.iter().find(|h| h.asset_code == asset_code)
+    }
+
+    /// The holdings on this page with a nonzero `total_quantity`.
+    pub fn nonzero(&self) -> impl Iterator<Item = &CryptoHoldings> {
+        self.results.iter().filter(|h| !h.total_quantity.is_zero())
+    }
+}
+
 /// Get holdings for the authenticated account, optionally filtering by asset code(s).
 ///
 /// `symbols` contains asset codes like "BTC"; when empty, returns all holdings.
-pub async fn get_crypto_holdings(rh: &Robinhood, symbols: Vec<&str>) -> Result<CryptoHoldingsResponse, reqwest::Error>{
-    let mut path = String::from("/api/v1/crypto/trading/holdings/");
-    if !symbols.is_empty() {
-        path.push('?');
-        for (i, sym) in symbols.iter().enumerate() {
-            if i > 0 {
-                path.push('&');
-            }
-            // Consider URL-escaping sym if needed
-            path.push_str("asset_code=");
-            path.push_str(sym);
+/// Only the first page is returned; see `get_crypto_holdings_page` and
+/// `get_all_crypto_holdings`.
+pub async fn get_crypto_holdings(rh: &Robinhood, symbols: Vec<&str>) -> Result<CryptoHoldingsResponse>{
+    get_crypto_holdings_page(rh, symbols, PageParams::default()).await
+}
+
+/// Every holding for the asset codes in `symbols`, with the codes that have
+/// no holding (or that Robinhood rejects) listed in `missing`.
+///
+/// Follows every page. See `get_best_price_partial` for how a rejected batch
+/// is retried code by code.
+pub async fn get_crypto_holdings_partial(rh: &Robinhood, symbols: Vec<&str>) -> Result<PartialResult<Vec<CryptoHoldings>>>{
+    fetch_partial(symbols, |holding: &CryptoHoldings| holding.asset_code.as_str(), |symbols| async move {
+        let symbols = symbols.iter().map(String::as_str).collect();
+        get_all_crypto_holdings(rh, symbols, PageParams::default()).await
+    }).await
+}
+
+/// Fetch one page of holdings selected by `page`.
+pub async fn get_crypto_holdings_page(rh: &Robinhood, symbols: Vec<&str>, page: PageParams) -> Result<CryptoHoldingsResponse>{
+    let path = paged_path("/api/v1/crypto/trading/holdings/", "asset_code", &symbols, &page, rh.account_number.as_deref());
+    let resp = rh.send(Method::GET, &path, "").await?;
+    decode_response(resp)
+}
+
+/// List every holding matching `symbols`, following `next` cursors until the
+/// last page. `page.limit` is kept as the page size.
+pub async fn get_all_crypto_holdings(rh: &Robinhood, symbols: Vec<&str>, page: PageParams) -> Result<Vec<CryptoHoldings>>{
+    collect_pages(page, |page| {
+        let symbols = symbols.clone();
+        async move {
+            let resp = get_crypto_holdings_page(rh, symbols, page).await?;
+            Ok((resp.results, resp.next))
         }
-    }
-    let headers = rh.auth_headers(&path, "GET", "");
-    let client = Client::new();
-    let resp = client
-        .get(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .send()
-        .await?.json::<CryptoHoldingsResponse>().await?;
-    Ok(resp)
+    }).await
+}
+
+#[test]
+fn test_holdings_locked_quantity_and_find(){
+    let resp: CryptoHoldingsResponse = serde_json::from_value(serde_json::json!({
+        "next": null, "previous": null, "results": [
+            {"account_number": "1", "asset_code": "BTC", "total_quantity": 1.5, "quantity_available_for_trading": 1.0},
+            {"account_number": "1", "asset_code": "ETH", "total_quantity": 1.0, "quantity_available_for_trading": 2.0}
+        ]
+    })).unwrap();
+    assert_eq!(resp.find("BTC").unwrap().locked_quantity(), Decimal::new(5, 1));
+    assert_eq!(resp.find("ETH").unwrap().locked_quantity(), Decimal::ZERO);
+    assert!(resp.find("DOGE").is_none());
+}
+
+#[tokio::test]
+async fn test_nonzero_holdings_drop_empty_balances(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let holding = |asset: &str, qty: f64| serde_json::json!({
+        "account_number": "1", "asset_code": asset, "total_quantity": qty, "quantity_available_for_trading": qty
+    });
+    let body = serde_json::json!({"next": null, "previous": null, "results": [holding("BTC", 0.5), holding("DOGE", 0.0)]});
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body.clone()))
+        .mount(&server)
+        .await;
+
+    let page: CryptoHoldingsResponse = serde_json::from_value(body).unwrap();
+    let assets: Vec<_> = page.nonzero().map(|h| h.asset_code.as_str()).collect();
+    assert_eq!(assets, ["BTC"]);
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let held = get_nonzero_holdings(&rh).await.unwrap();
+    assert_eq!(held.len(), 1);
+    assert_eq!(held[0].asset_code, "BTC");
+}
+
+/// Every holding with a nonzero `total_quantity`, across all pages.
+///
+/// Robinhood may list assets the account used to hold with a zero balance or
+/// leave them out entirely; either way they are absent here.
+pub async fn get_nonzero_holdings(rh: &Robinhood) -> Result<Vec<CryptoHoldings>>{
+    let mut holdings = get_all_crypto_holdings(rh, vec![], PageParams::default()).await?;
+    holdings.retain(|h| !h.total_quantity.is_zero());
+    Ok(holdings)
 }
 
 #[tokio::test]
 async fn test_get_crypto_holdings(){
-    let rh = Robinhood::from_env();
+    let rh = crate::auth::cassette_robinhood("crypto_holdings");
     match get_crypto_holdings(&rh, vec!["BTC"]).await{
         Ok(resp) => {
             assert_eq!(resp.next, None);
@@ -139,39 +658,171 @@ async fn test_get_crypto_holdings(){
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Whether an order buys or sells the asset.
+pub enum OrderSide {
+    Buy,
+    Sell,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl std::fmt::Display for OrderSide {
+    /// The wire name, e.g. `sell`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+            OrderSide::Unknown(other) => other,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// The kind of order, which determines which `*_order_config` is used.
+pub enum OrderType {
+    Market,
+    Limit,
+    StopLoss,
+    StopLimit,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl std::fmt::Display for OrderType {
+    /// The wire name, e.g. `stop_limit`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+            OrderType::StopLoss => "stop_loss",
+            OrderType::StopLimit => "stop_limit",
+            OrderType::Unknown(other) => other,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// Lifecycle state of an order.
+pub enum OrderState {
+    Open,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Failed,
+    Rejected,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl std::fmt::Display for OrderState {
+    /// The wire name, e.g. `rejected`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OrderState::Open => "open",
+            OrderState::PartiallyFilled => "partially_filled",
+            OrderState::Filled => "filled",
+            OrderState::Canceled => "canceled",
+            OrderState::Failed => "failed",
+            OrderState::Rejected => "rejected",
+            OrderState::Unknown(other) => other,
+        })
+    }
+}
+
+impl OrderState {
+    /// Whether the order can no longer change (filled, canceled, failed or rejected).
+    ///
+    /// Unknown states are treated as still working.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderState::Filled | OrderState::Canceled | OrderState::Failed | OrderState::Rejected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How long an order stays working before it expires.
+pub enum TimeInForce {
+    /// Good till canceled.
+    Gtc,
+    /// Good for the day.
+    Gfd,
+    /// Immediate or cancel.
+    Ioc,
+    /// Fill or kill.
+    Fok,
+    /// A value this crate does not know about yet.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[test]
+fn test_order_enums_wire_format(){
+    assert_eq!(serde_json::to_string(&OrderType::StopLimit).unwrap(), "\"stop_limit\"");
+    assert_eq!(serde_json::to_string(&TimeInForce::Gfd).unwrap(), "\"gfd\"");
+    let state: OrderState = serde_json::from_str("\"partially_filled\"").unwrap();
+    assert_eq!(state, OrderState::PartiallyFilled);
+    let state: OrderState = serde_json::from_str("\"brand_new_state\"").unwrap();
+    assert_eq!(state, OrderState::Unknown("brand_new_state".to_string()));
+    assert_eq!(serde_json::to_string(&state).unwrap(), "\"brand_new_state\"");
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Paginated response for listing crypto orders.
 pub struct CryptoOrdersResponse {
-    pub next: Option<String>,
-    pub previous: Option<String>,
+    #[serde(default, with = "cursor_url")]
+    pub next: Option<Cursor>,
+    #[serde(default, with = "cursor_url")]
+    pub previous: Option<Cursor>,
     pub results: Vec<CryptoOrder>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// A crypto order as returned by Robinhood's trading API.
+///
+/// This is the crate's common order model: a `CreateCryptoOrderResponse`
+/// converts into it losslessly with `From`, so helpers such as
+/// `compute_cost_basis` handle new and listed orders alike.
+///
+/// Hashes by `id` only, so sets and maps of orders are keyed by order id.
 pub struct CryptoOrder {
     pub id: String,
     pub account_number: String,
     pub symbol: String,
     pub client_order_id: String,
-    pub side: String,
+    pub side: OrderSide,
     pub executions: Vec<Executions>,
 
     #[serde(rename = "type")]
-    pub order_type: String,
+    pub order_type: OrderType,
+
+    pub state: OrderState,
 
-    pub state: String,
+    /// Why Robinhood rejected the order; only sent for `rejected` orders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reject_reason: Option<String>,
 
-    // May be absent or null
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    // Both may be absent or null until the order has fills
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub average_price: Option<Decimal>,
 
-    // Always present (in your sample); string number
-    #[serde(with = "rust_decimal::serde::str")]
-    pub filled_asset_quantity: Decimal,
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
+    pub filled_asset_quantity: Option<Decimal>,
 
-    pub created_at: String,
-    pub updated_at: String,
+    #[serde(with = "crate::time::rfc3339")]
+    pub created_at: Timestamp,
+    #[serde(with = "crate::time::rfc3339")]
+    pub updated_at: Timestamp,
 
     pub market_order_config: Option<MarketOrderConfig>,
     pub limit_order_config: Option<LimitOrderConfig>,
@@ -179,75 +830,345 @@ pub struct CryptoOrder {
     pub stop_limit_order_config: Option<StopLimitOrderConfig>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// An execution fill for an order.
 pub struct Executions {
-    pub effective_price: String,
-    pub quantity: String,
-    pub timestamp: String,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub effective_price: Decimal,
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
+    pub quantity: Decimal,
+    #[serde(with = "crate::time::rfc3339")]
+    pub timestamp: Timestamp,
+}
+
+impl Hash for CryptoOrder {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl CryptoOrder {
+    /// Total asset quantity filled across all executions (zero if none).
+    pub fn total_filled_quantity(&self) -> Decimal {
+        self.executions.iter().map(|e| e.quantity).sum()
+    }
+
+    /// Total quote value filled, i.e. the sum of price * quantity per execution.
+    pub fn total_filled_value(&self) -> Decimal {
+        self.executions.iter().map(|e| e.effective_price * e.quantity).sum()
+    }
+
+    /// Volume-weighted average price across this order's executions.
+    ///
+    /// Returns `None` when nothing has been filled yet.
+    pub fn average_fill_price(&self) -> Option<Decimal> {
+        let quantity = self.total_filled_quantity();
+        if quantity.is_zero() {
+            return None;
+        }
+        Some(self.total_filled_value() / quantity)
+    }
+
+    /// The order's terms, taken from the `*_order_config` that `order_type`
+    /// says is in use.
+    ///
+    /// Returns `OrderConfig::Unknown` for an order type this crate doesn't
+    /// know, or when the matching config is missing (e.g. a `limit` order
+    /// with no `limit_order_config`); configs for other types are ignored.
+    pub fn config(&self) -> OrderConfig {
+        let config = match self.order_type {
+            OrderType::Market => self.market_order_config.clone().map(OrderConfig::Market),
+            OrderType::Limit => self.limit_order_config.clone().map(OrderConfig::Limit),
+            OrderType::StopLoss => self.stop_loss_order_config.clone().map(OrderConfig::StopLoss),
+            OrderType::StopLimit => self.stop_limit_order_config.clone().map(OrderConfig::StopLimit),
+            OrderType::Unknown(_) => None,
+        };
+        config.unwrap_or(OrderConfig::Unknown)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+/// An order's terms, one variant per `OrderType`; see `CryptoOrder::config`.
+pub enum OrderConfig {
+    Market(MarketOrderConfig),
+    Limit(LimitOrderConfig),
+    StopLoss(StopLossOrderConfig),
+    StopLimit(StopLimitOrderConfig),
+    /// The order type is unknown, or its config was missing from the response.
+    Unknown,
+}
+
+#[test]
+fn test_order_config_follows_order_type(){
+    let order = |json: serde_json::Value| serde_json::from_value::<CryptoOrder>(json).unwrap().config();
+    assert_eq!(order(order_json("o1", "open")), OrderConfig::Market(MarketOrderConfig { asset_quantity: Decimal::ONE }));
+
+    let mut stop_limit = order_json("o2", "open");
+    stop_limit["type"] = "stop_limit".into();
+    stop_limit["stop_limit_order_config"] = serde_json::json!({"asset_quantity": "2", "limit_price": "9", "stop_price": "10", "time_in_force": "gtc"});
+    match order(stop_limit) {
+        OrderConfig::StopLimit(config) => assert_eq!((config.stop_price, config.limit_price), (Some(Decimal::from(10)), Some(Decimal::from(9)))),
+        other => panic!("expected a stop-limit config, got {other:?}"),
+    }
+
+    // says limit, but only carries a market config
+    let mut malformed = order_json("o3", "open");
+    malformed["type"] = "limit".into();
+    assert_eq!(order(malformed), OrderConfig::Unknown);
+    let mut unknown = order_json("o4", "open");
+    unknown["type"] = "trailing_stop".into();
+    assert_eq!(order(unknown), OrderConfig::Unknown);
+}
+
+/// `(quote_amount, asset_quantity, stop_price, limit_price)` from the first
+/// order config that is set.
+pub(crate) fn config_terms(
+    market: &Option<MarketOrderConfig>,
+    limit: &Option<LimitOrderConfig>,
+    stop_loss: &Option<StopLossOrderConfig>,
+    stop_limit: &Option<StopLimitOrderConfig>,
+) -> (Option<Decimal>, Option<Decimal>, Option<Decimal>, Option<Decimal>) {
+    if let Some(c) = market {
+        (None, Some(c.asset_quantity), None, None)
+    } else if let Some(c) = limit {
+        (c.quote_amount, c.asset_quantity, None, c.limit_price)
+    } else if let Some(c) = stop_loss {
+        (c.quote_amount, c.asset_quantity, c.stop_price, None)
+    } else if let Some(c) = stop_limit {
+        (c.quote_amount, c.asset_quantity, c.stop_price, c.limit_price)
+    } else {
+        (None, None, None, None)
+    }
+}
+
+impl std::fmt::Display for CryptoOrder {
+    /// One-line summary such as `buy 1 XRP-USD @ limit 1.5 [open]`.
+    ///
+    /// Quote-denominated orders show the notional in the pair's quote currency instead, e.g.
+    /// `buy 100 EUR of BTC-EUR @ market [filled]`,
+    /// and a rejected order shows its reason, e.g. `[rejected: insufficient buying power]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (quote_amount, asset_quantity, stop_price, limit_price) = config_terms(
+            &self.market_order_config,
+            &self.limit_order_config,
+            &self.stop_loss_order_config,
+            &self.stop_limit_order_config,
+        );
+        match (asset_quantity, quote_amount) {
+            (Some(quantity), _) => write!(f, "{} {} {}", self.side, quantity.normalize(), self.symbol)?,
+            (None, Some(amount)) => match self.symbol.rsplit_once('-') {
+                Some((_, quote_code)) => write!(f, "{} {} {quote_code} of {}", self.side, amount.normalize(), self.symbol)?,
+                None => write!(f, "{} {} of {}", self.side, amount.normalize(), self.symbol)?,
+            },
+            (None, None) => write!(f, "{} {}", self.side, self.symbol)?,
+        }
+        match self.order_type {
+            OrderType::StopLoss | OrderType::StopLimit => write!(f, " @ stop")?,
+            _ => write!(f, " @ {}", self.order_type)?,
+        }
+        if let Some(stop) = stop_price {
+            write!(f, " {}", stop.normalize())?;
+        }
+        if let Some(limit) = limit_price {
+            if self.order_type == OrderType::StopLimit {
+                write!(f, " limit")?;
+            }
+            write!(f, " {}", limit.normalize())?;
+        }
+        match &self.reject_reason {
+            Some(reason) => write!(f, " [{}: {reason}]", self.state),
+            None => write!(f, " [{}]", self.state),
+        }
+    }
+}
+
+/// Sample account payload with buying power in `currency`.
+#[cfg(test)]
+pub(crate) fn account_json(currency: &str) -> serde_json::Value {
+    serde_json::json!({"account_number": "1", "status": "active", "buying_power": "1000", "buying_power_currency": currency})
+}
+
+/// Sample order payload in the shape the orders endpoints return.
+#[cfg(test)]
+fn order_json(id: &str, state: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id, "account_number": "1", "symbol": "BTC-USD", "client_order_id": id,
+        "side": "buy", "executions": [], "type": "market", "state": state,
+        "average_price": null, "filled_asset_quantity": "0",
+        "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+        "market_order_config": {"asset_quantity": "1"}, "limit_order_config": null,
+        "stop_loss_order_config": null, "stop_limit_order_config": null
+    })
+}
+
+#[test]
+fn test_orders_and_pairs_hash_by_identifier(){
+    use std::collections::HashSet;
+
+    let open: CryptoOrder = serde_json::from_value(order_json("o1", "open")).unwrap();
+    let filled: CryptoOrder = serde_json::from_value(order_json("o1", "filled")).unwrap();
+    assert_ne!(open, filled);
+    let orders: HashSet<_> = [open.clone(), open.clone(), filled].into_iter().collect();
+    assert_eq!(orders.len(), 2);
+    assert!(orders.contains(&open));
+}
+
+#[test]
+fn test_unfilled_order_has_no_fill_fields(){
+    let order_json = serde_json::json!({
+        "id": "o1", "account_number": "1", "symbol": "XRP-USD", "client_order_id": "c1",
+        "side": "buy", "executions": [], "type": "limit", "state": "open",
+        "average_price": null, "filled_asset_quantity": null,
+        "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+        "market_order_config": null,
+        "limit_order_config": {"asset_quantity": "10", "limit_price": "0.5", "time_in_force": "gtc"},
+        "stop_loss_order_config": null, "stop_limit_order_config": null
+    });
+    let order: CryptoOrder = serde_json::from_value(order_json.clone()).unwrap();
+    assert_eq!((order.average_price, order.filled_asset_quantity), (None, None));
+    let created: CreateCryptoOrderResponse = serde_json::from_value(order_json.clone()).unwrap();
+    assert_eq!((created.average_price, created.filled_asset_quantity), (None, None));
+
+    // the keys may also be left out entirely, or sent as numbers once filled
+    let mut sparse = order_json;
+    sparse.as_object_mut().unwrap().retain(|k, _| k != "average_price" && k != "filled_asset_quantity");
+    let order: CryptoOrder = serde_json::from_value(sparse.clone()).unwrap();
+    assert_eq!(order.filled_asset_quantity, None);
+    sparse["average_price"] = serde_json::json!(0.5);
+    sparse["filled_asset_quantity"] = serde_json::json!("10");
+    let created: CreateCryptoOrderResponse = serde_json::from_value(sparse).unwrap();
+    assert_eq!((created.average_price, created.filled_asset_quantity), (Some(Decimal::new(5, 1)), Some(Decimal::from(10))));
+    assert_eq!(CreateCryptoOrderResponse::from(order).filled_asset_quantity, None);
+}
+
+#[test]
+fn test_fill_summary(){
+    let mut json = order_json("o1", "filled");
+    json["executions"] = serde_json::json!([
+        {"effective_price": "100", "quantity": "1", "timestamp": "2024-01-01T00:00:00Z"},
+        {"effective_price": "110", "quantity": "3", "timestamp": "2024-01-01T00:00:01.5Z"}
+    ]);
+    let order: CryptoOrder = serde_json::from_value(json).unwrap();
+    assert_eq!(order.total_filled_quantity(), Decimal::from(4));
+    assert_eq!(order.total_filled_value(), Decimal::from(430));
+    assert_eq!(order.average_fill_price(), Some(Decimal::new(1075, 1)));
+
+    let open: CryptoOrder = serde_json::from_value(order_json("o2", "open")).unwrap();
+    assert_eq!(open.total_filled_quantity(), Decimal::ZERO);
+    assert_eq!(open.total_filled_value(), Decimal::ZERO);
+    assert_eq!(open.average_fill_price(), None);
+}
+
+#[test]
+fn test_order_display(){
+    let market: CryptoOrder = serde_json::from_value(order_json("o1", "partially_filled")).unwrap();
+    assert_eq!(market.to_string(), "buy 1 BTC-USD @ market [partially_filled]");
+
+    let mut json = order_json("o2", "open");
+    json["symbol"] = "XRP-USD".into();
+    json["type"] = "limit".into();
+    json["market_order_config"] = serde_json::Value::Null;
+    json["limit_order_config"] = serde_json::json!({"asset_quantity": "1.000", "limit_price": "1.50"});
+    let limit: CryptoOrder = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(limit.to_string(), "buy 1 XRP-USD @ limit 1.5 [open]");
+
+    json["side"] = "sell".into();
+    json["type"] = "stop_limit".into();
+    json["limit_order_config"] = serde_json::Value::Null;
+    json["stop_limit_order_config"] = serde_json::json!({"quote_amount": "100.00", "stop_price": "0.9", "limit_price": "0.85"});
+    let stop_limit: CryptoOrder = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(stop_limit.to_string(), "sell 100 USD of XRP-USD @ stop 0.9 limit 0.85 [open]");
+    json["symbol"] = "XRP-EUR".into();
+    let in_euros: CryptoOrder = serde_json::from_value(json).unwrap();
+    assert_eq!(in_euros.to_string(), "sell 100 EUR of XRP-EUR @ stop 0.9 limit 0.85 [open]");
+
+    let mut json = order_json("o3", "rejected");
+    json["reject_reason"] = "insufficient buying power".into();
+    let rejected: CryptoOrder = serde_json::from_value(json).unwrap();
+    assert_eq!(rejected.state, OrderState::Rejected);
+    assert_eq!(rejected.to_string(), "buy 1 BTC-USD @ market [rejected: insufficient buying power]");
+    assert_eq!(CreateCryptoOrderResponse::from(rejected).reject_reason.as_deref(), Some("insufficient buying power"));
+    assert!(serde_json::to_value(&market).unwrap().get("reject_reason").is_none());
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 /// Parameters for a market order.
 pub struct MarketOrderConfig {
-    #[serde(with = "rust_decimal::serde::str")]
+    #[serde(with = "crate::decimal::flexible_decimal::str")]
     pub asset_quantity: Decimal,
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 /// Parameters for a limit order.
 pub struct LimitOrderConfig {
     // Any of these may be omitted; they also arrive as strings
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quote_amount: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub asset_quantity: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub limit_price: Option<Decimal>,
     // Can be absent; plain Option<String> doesn't need `default`
-    pub time_in_force: Option<String>,
+    pub time_in_force: Option<TimeInForce>,
+}
+
+#[test]
+fn test_order_configs_support_struct_update(){
+    let price = Decimal::new(105, 1);
+    let config = LimitOrderConfig { limit_price: Some(price), ..Default::default() };
+    let built = LimitOrderConfig::builder().limit_price(Some(price)).time_in_force(None).build();
+    assert_eq!(config, built);
+    assert_eq!(StopLimitOrderConfig::default().stop_price, None);
+    assert_eq!(StopLossOrderConfig::default().time_in_force, None);
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 /// Parameters for a stop-loss order.
 pub struct StopLossOrderConfig {
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quote_amount: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub asset_quantity: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub stop_price: Option<Decimal>,
-    pub time_in_force: Option<String>,
+    pub time_in_force: Option<TimeInForce>,
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 /// Parameters for a stop-limit order.
 pub struct StopLimitOrderConfig {
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quote_amount: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub asset_quantity: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub limit_price: Option<Decimal>,
-    #[serde(default, with = "rust_decimal::serde::str_option")]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub stop_price: Option<Decimal>,
-    pub time_in_force: Option<String>,
+    pub time_in_force: Option<TimeInForce>,
 }
 
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
 /// Query parameters for listing crypto orders.
 pub struct GetCryptoOrderParams{
     #[builder(default, setter(strip_option, into))]
@@ -264,13 +1185,13 @@ pub struct GetCryptoOrderParams{
     pub id: Option<String>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub side: Option<String>,
+    pub side: Option<OrderSide>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub state: Option<String>,
+    pub state: Option<OrderState>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none", rename = "type")]
-    pub type_: Option<String>,
+    pub type_: Option<OrderType>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at_start: Option<String>,
@@ -279,51 +1200,276 @@ pub struct GetCryptoOrderParams{
     pub updated_at_end: Option<String>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub cursor: Option<String>,
+    pub cursor: Option<Cursor>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
+    /// Account to list orders for; defaults to the client's
+    /// `with_account_number`, if any.
+    #[builder(default, setter(strip_option, into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_number: Option<String>,
+}
+/// The order-list path for `params`, scoped to the client's account unless
+/// `params` names one.
+fn orders_path(rh: &Robinhood, mut params: GetCryptoOrderParams) -> String {
+    if params.account_number.is_none() {
+        params.account_number = rh.account_number.clone();
+    }
+    path_with_params("/api/v1/crypto/trading/orders/", &params)
 }
+
 /// List crypto orders for the authenticated account using optional filters.
-pub async fn get_crypto_orders(rh: &Robinhood,params: GetCryptoOrderParams) -> Result<CryptoOrdersResponse, reqwest::Error>{
-    let path = String::from("/api/v1/crypto/trading/orders/");
-    let headers = rh.auth_headers(&path, "GET", "");
-    let client = Client::new();
-    let resp = client
-        .get(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .query(&params)
-        .send()
-        .await?.json::<CryptoOrdersResponse>().await?;
-    Ok(resp)
+pub async fn get_crypto_orders(rh: &Robinhood,params: GetCryptoOrderParams) -> Result<CryptoOrdersResponse>{
+    let path = orders_path(rh, params);
+    let resp = rh.send(Method::GET, &path, "").await?;
+    decode_response(resp)
+}
+
+/// Fetch a single crypto order by its server-side `id`.
+///
+/// Returns `RobinError::NotFound` if no order with that id exists.
+pub async fn get_crypto_order(rh: &Robinhood, id: &str) -> Result<CryptoOrder>{
+    let path = format!("/api/v1/crypto/trading/orders/{id}/");
+    let resp = rh.send(Method::GET, &path, "").await?;
+    match decode_response(resp) {
+        Err(RobinError::Api { status: 404, body, .. }) => Err(RobinError::NotFound(body)),
+        other => other,
+    }
 }
 
 #[tokio::test]
-async fn test_get_crypto_orders(){
-    let rh = Robinhood::from_env();
-    match get_crypto_orders(&rh, GetCryptoOrderParams::builder().build()).await{
-        Ok(resp) => {
-            assert_eq!(resp.previous, None);
+async fn test_get_crypto_order_not_found(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/missing/"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("{\"detail\":\"Not found.\"}"))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    match get_crypto_order(&rh, "missing").await {
+        Err(RobinError::NotFound(body)) => assert!(body.contains("Not found")),
+        other => panic!("expected not found, got {:?}", other),
+    }
+}
+
+/// Poll an order every `poll_interval` until it reaches a terminal state.
+///
+/// Returns the terminal order, or `RobinError::OrderTimeout` carrying the last
+/// observed order if it is still working after `timeout`.
+pub async fn await_order_terminal(rh: &Robinhood, id: &str, poll_interval: Duration, timeout: Duration) -> Result<CryptoOrder>{
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let order = get_crypto_order(rh, id).await?;
+        if order.state.is_terminal() {
+            return Ok(order);
         }
-        Err(e) => {
-            panic!("Error with crypto orders: {}", e);
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Err(RobinError::OrderTimeout(Box::new(order)));
         }
+        tokio::time::sleep(poll_interval.min(deadline - now)).await;
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
-/// Parameters for creating a crypto order.
-pub struct CreateCyptoOrderParams{
-    pub symbol: String,
-    pub client_order_id: String,
-    pub side: String,
-    #[serde(rename = "type")]
-    pub order_type: String,
-    #[builder(default, setter(strip_option, into))]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub market_order_config: Option<MarketOrderConfig>,
-    #[builder(default, setter(strip_option, into))]
-    #[serde(skip_serializing_if = "Option::is_none")]
+#[tokio::test]
+async fn test_await_order_terminal(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let order = |state: &str| order_json("o1", state);
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(order("open")))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(order("filled")))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let interval = Duration::from_millis(5);
+    let filled = await_order_terminal(&rh, "o1", interval, Duration::from_secs(5)).await.unwrap();
+    assert_eq!(filled.state, OrderState::Filled);
+
+    server.reset().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(order("open")))
+        .mount(&server)
+        .await;
+    match await_order_terminal(&rh, "o1", interval, Duration::from_millis(30)).await {
+        Err(RobinError::OrderTimeout(last)) => assert_eq!(last.state, OrderState::Open),
+        other => panic!("expected order timeout, got {:?}", other),
+    }
+}
+
+/// Lazily stream every crypto order matching `params`, one order at a time.
+///
+/// The next page is only requested once the orders from the current page have
+/// been consumed, so memory use is bounded by the page size. A failed page
+/// fetch is yielded as an `Err` item and ends the stream. The caller's `limit`
+/// is kept as the page size, and a cursor that was already visited stops
+/// pagination rather than looping.
+pub fn stream_crypto_orders(rh: &Robinhood, params: GetCryptoOrderParams) -> impl Stream<Item = Result<CryptoOrder>> + '_ {
+    let state = (Some(params), VecDeque::new(), HashSet::new());
+    futures::stream::unfold(state, move |(mut params, mut buffer, mut seen)| async move {
+        loop {
+            if let Some(order) = buffer.pop_front() {
+                return Some((Ok(order), (params, buffer, seen)));
+            }
+            let current: GetCryptoOrderParams = params.take()?;
+            match get_crypto_orders(rh, current.clone()).await {
+                Ok(page) => {
+                    buffer.extend(page.results);
+                    params = match page.next {
+                        Some(cursor) if seen.insert(cursor.clone()) => {
+                            Some(GetCryptoOrderParams { cursor: Some(cursor), ..current })
+                        }
+                        _ => None,
+                    };
+                }
+                Err(e) => return Some((Err(e), (None, buffer, seen))),
+            }
+        }
+    })
+}
+
+/// List every crypto order matching `params`, following `next` cursors until
+/// the last page.
+///
+/// See `stream_crypto_orders` for a lazy variant that doesn't buffer every
+/// order in memory.
+pub async fn get_all_crypto_orders(rh: &Robinhood, params: GetCryptoOrderParams) -> Result<Vec<CryptoOrder>>{
+    stream_crypto_orders(rh, params).try_collect().await
+}
+
+#[tokio::test]
+async fn test_get_crypto_orders(){
+    let rh = crate::auth::cassette_robinhood("crypto_orders");
+    match get_crypto_orders(&rh, GetCryptoOrderParams::builder().build()).await{
+        Ok(resp) => {
+            assert_eq!(resp.previous, None);
+        }
+        Err(e) => {
+            panic!("Error with crypto orders: {}", e);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_orders_request_is_sent_exactly_as_signed(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": []})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri()).with_account_number("ACCT 1");
+    // reserved characters that a second round of encoding would change
+    let params = GetCryptoOrderParams::builder()
+        .created_at_start("2024-06-01T09:30:00+02:00")
+        .symbol("BTC-USD")
+        .state(OrderState::Open)
+        .limit(5u32)
+        .build();
+    let signed = orders_path(&rh, params.clone());
+    assert!(signed.contains("created_at_start=2024-06-01T09%3A30%3A00%2B02%3A00"), "{signed}");
+
+    let built = rh.signed_request_builder(Method::GET, &signed, "").unwrap().build().unwrap();
+    let sent = format!("{}?{}", built.url().path(), built.url().query().unwrap());
+    assert_eq!(sent, signed);
+
+    get_crypto_orders(&rh, params).await.unwrap();
+    let req = &server.received_requests().await.unwrap()[0];
+    assert_eq!(format!("{}?{}", req.url.path(), req.url.query().unwrap()), signed);
+    crate::auth::assert_signed(&rh, req);
+}
+
+#[tokio::test]
+async fn test_get_all_crypto_orders_follows_cursor(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let order = |id: &str| order_json(id, "filled");
+    let server = MockServer::start().await;
+    let next = format!("{}/api/v1/crypto/trading/orders/?cursor=abc&limit=1", server.uri());
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(query_param("cursor", "abc"))
+        .and(query_param("limit", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            // same cursor again must not cause an infinite loop
+            "next": next, "previous": null, "results": [order("2")]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": next, "previous": null, "results": [order("1")]
+        })))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let orders = get_all_crypto_orders(&rh, GetCryptoOrderParams::builder().limit(1u32).build()).await.unwrap();
+    let expected: Vec<CryptoOrder> = ["1", "2"].iter().map(|id| serde_json::from_value(order(id)).unwrap()).collect();
+    assert_eq!(orders, expected);
+}
+
+#[tokio::test]
+async fn test_stream_crypto_orders_surfaces_page_error(){
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(query_param("cursor", "abc"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("boom"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": format!("{}/api/v1/crypto/trading/orders/?cursor=abc", server.uri()),
+            "previous": null,
+            "results": []
+        })))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let items: Vec<_> = stream_crypto_orders(&rh, GetCryptoOrderParams::builder().build()).collect().await;
+    assert_eq!(items.len(), 1);
+    assert!(matches!(items[0], Err(RobinError::Api { status: 500, .. })));
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+/// Parameters for creating a crypto order.
+pub struct CreateCyptoOrderParams{
+    pub symbol: String,
+    pub client_order_id: String,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    #[builder(default, setter(strip_option, into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_order_config: Option<MarketOrderConfig>,
+    #[builder(default, setter(strip_option, into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit_order_config: Option<LimitOrderConfig>,
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -331,26 +1477,124 @@ pub struct CreateCyptoOrderParams{
     #[builder(default, setter(strip_option, into))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_limit_order_config: Option<StopLimitOrderConfig>,
+    /// Account to place the order in; defaults to the client's
+    /// `with_account_number`, if any.
+    #[builder(default, setter(strip_option, into))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why `CreateCyptoOrderParams` describe an order Robinhood would reject.
+pub enum OrderValidationError {
+    /// More than one `*_order_config` is set.
+    MultipleConfigs,
+    /// The config that is set does not belong to the declared `order_type`.
+    ConfigMismatch { order_type: OrderType, config: &'static str },
+    /// A config or field required by `order_type` is missing.
+    MissingField { order_type: OrderType, field: &'static str },
 }
 
-#[derive(Debug, Serialize, Deserialize, TypedBuilder)]
+impl std::fmt::Display for OrderValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderValidationError::MultipleConfigs => write!(f, "only one order config may be set"),
+            OrderValidationError::ConfigMismatch { order_type, config } => {
+                write!(f, "{order_type} order cannot use {config}")
+            }
+            OrderValidationError::MissingField { order_type, field } => {
+                write!(f, "{order_type} order requires {field}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrderValidationError {}
+
+impl CreateCyptoOrderParams {
+    /// Check that exactly one config is set, that it matches `order_type`, and
+    /// that it carries the fields that type needs (a quantity, plus
+    /// `limit_price` and/or `stop_price`).
+    ///
+    /// Orders with an `Unknown` type are only checked for a single config.
+    /// `create_crypto_order` runs this before anything is sent.
+    pub fn validate(&self) -> std::result::Result<(), OrderValidationError> {
+        let set = [
+            self.market_order_config.as_ref().map(|_| "market_order_config"),
+            self.limit_order_config.as_ref().map(|_| "limit_order_config"),
+            self.stop_loss_order_config.as_ref().map(|_| "stop_loss_order_config"),
+            self.stop_limit_order_config.as_ref().map(|_| "stop_limit_order_config"),
+        ];
+        let mut set = set.into_iter().flatten();
+        let config = set.next();
+        if set.next().is_some() {
+            return Err(OrderValidationError::MultipleConfigs);
+        }
+
+        let (expected, needs_limit, needs_stop) = match self.order_type {
+            OrderType::Market => ("market_order_config", false, false),
+            OrderType::Limit => ("limit_order_config", true, false),
+            OrderType::StopLoss => ("stop_loss_order_config", false, true),
+            OrderType::StopLimit => ("stop_limit_order_config", true, true),
+            OrderType::Unknown(_) => return Ok(()),
+        };
+        let order_type = self.order_type.clone();
+        let field = match config {
+            Some(config) if config != expected => {
+                return Err(OrderValidationError::ConfigMismatch { order_type, config });
+            }
+            Some(_) => {
+                let (quote_amount, asset_quantity, stop_price, limit_price) = config_terms(
+                    &self.market_order_config,
+                    &self.limit_order_config,
+                    &self.stop_loss_order_config,
+                    &self.stop_limit_order_config,
+                );
+                if asset_quantity.or(quote_amount).is_none() {
+                    "asset_quantity or quote_amount"
+                } else if needs_limit && limit_price.is_none() {
+                    "limit_price"
+                } else if needs_stop && stop_price.is_none() {
+                    "stop_price"
+                } else {
+                    return Ok(());
+                }
+            }
+            None => expected,
+        };
+        Err(OrderValidationError::MissingField { order_type, field })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+#[non_exhaustive]
 /// Response returned after creating a crypto order.
+///
+/// Carries the same fields as `CryptoOrder`; convert with `.into()` to use
+/// the order helpers.
 pub struct CreateCryptoOrderResponse{
     pub id: String,
     pub account_number: String,
     pub symbol: String,
     pub client_order_id: String,
-    pub side: String,
+    pub side: OrderSide,
     pub executions: Vec<Executions>,
     #[serde(rename = "type")]
-    pub order_type: String,
-    pub state: String,
-    #[serde(with = "rust_decimal::serde::float_option", default)]
+    pub order_type: OrderType,
+    pub state: OrderState,
+    /// Why Robinhood rejected the order; only sent for `rejected` orders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[builder(default)]
+    pub reject_reason: Option<String>,
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub average_price: Option<Decimal>,
-    #[serde(with = "rust_decimal::serde::float_option", default)]
+    #[serde(default, with = "crate::decimal::flexible_decimal::str_option")]
     pub filled_asset_quantity: Option<Decimal>,
-    pub created_at: String,
-    pub updated_at: String,
+    #[serde(with = "crate::time::rfc3339")]
+    pub created_at: Timestamp,
+    #[serde(with = "crate::time::rfc3339")]
+    pub updated_at: Timestamp,
     pub market_order_config: Option<MarketOrderConfig>,
     pub limit_order_config: Option<LimitOrderConfig>,
     pub stop_loss_order_config: Option<StopLossOrderConfig>,
@@ -358,53 +1602,1087 @@ pub struct CreateCryptoOrderResponse{
 }
 
 /// Create a new crypto order with the provided parameters.
-pub async fn create_crypto_order(rh: &Robinhood, param: CreateCyptoOrderParams) -> Result<CreateCryptoOrderResponse, reqwest::Error>{
+///
+/// The parameters are checked with `CreateCyptoOrderParams::validate` first,
+/// so a malformed order fails with `RobinError::InvalidOrder` without a round trip.
+///
+/// In dry-run mode (`Robinhood::with_dry_run`) the request is signed but not
+/// sent, and an `open` order with a made-up `dry-run-` id is returned.
+pub async fn create_crypto_order(rh: &Robinhood, param: CreateCyptoOrderParams) -> Result<CreateCryptoOrderResponse>{
+    param.validate()?;
+    if rh.dry_run {
+        create_crypto_order_dry_run(rh, &param)?;
+        return Ok(CreateCryptoOrderResponse::dry_run(param));
+    }
+    let path = "/api/v1/crypto/trading/orders/";
+    let body = order_body(rh, &param)?;
+    let resp = rh.send(Method::POST, path, &body).await?;
+    decode_response(resp)
+}
+
+/// Build and sign the request `create_crypto_order` would send, without
+/// sending it, so the exact URL, headers and JSON body can be inspected.
+pub fn create_crypto_order_dry_run(rh: &Robinhood, param: &CreateCyptoOrderParams) -> Result<SignedRequest>{
+    param.validate()?;
+    let body = order_body(rh, param)?;
+    rh.signed_request(&Method::POST, "/api/v1/crypto/trading/orders/", &body)
+}
+
+/// The JSON body for creating `param`, in the client's account unless
+/// `param` names one.
+fn order_body(rh: &Robinhood, param: &CreateCyptoOrderParams) -> Result<String>{
+    match (&param.account_number, &rh.account_number) {
+        (None, Some(account)) => {
+            let param = CreateCyptoOrderParams { account_number: Some(account.clone()), ..param.clone() };
+            Ok(serde_json::to_string(&param)?)
+        }
+        _ => Ok(serde_json::to_string(param)?),
+    }
+}
+
+impl CreateCryptoOrderResponse {
+    /// The response a dry-run client reports for `param`: an unfilled `open`
+    /// order stamped with the current time.
+    fn dry_run(param: CreateCyptoOrderParams) -> Self {
+        let now = crate::time::now();
+        CreateCryptoOrderResponse {
+            id: format!("dry-run-{}", Uuid::new_v4()),
+            account_number: String::new(),
+            symbol: param.symbol,
+            client_order_id: param.client_order_id,
+            side: param.side,
+            executions: Vec::new(),
+            order_type: param.order_type,
+            state: OrderState::Open,
+            reject_reason: None,
+            average_price: None,
+            filled_asset_quantity: None,
+            created_at: now.to_owned(),
+            updated_at: now,
+            market_order_config: param.market_order_config,
+            limit_order_config: param.limit_order_config,
+            stop_loss_order_config: param.stop_loss_order_config,
+            stop_limit_order_config: param.stop_limit_order_config,
+        }
+    }
+}
+
+impl From<CryptoOrder> for CreateCryptoOrderResponse {
+    fn from(order: CryptoOrder) -> Self {
+        CreateCryptoOrderResponse {
+            id: order.id,
+            account_number: order.account_number,
+            symbol: order.symbol,
+            client_order_id: order.client_order_id,
+            side: order.side,
+            executions: order.executions,
+            order_type: order.order_type,
+            state: order.state,
+            reject_reason: order.reject_reason,
+            average_price: order.average_price,
+            filled_asset_quantity: order.filled_asset_quantity,
+            created_at: order.created_at,
+            updated_at: order.updated_at,
+            market_order_config: order.market_order_config,
+            limit_order_config: order.limit_order_config,
+            stop_loss_order_config: order.stop_loss_order_config,
+            stop_limit_order_config: order.stop_limit_order_config,
+        }
+    }
+}
+
+impl From<CreateCryptoOrderResponse> for CryptoOrder {
+    fn from(resp: CreateCryptoOrderResponse) -> Self {
+        CryptoOrder {
+            id: resp.id,
+            account_number: resp.account_number,
+            symbol: resp.symbol,
+            client_order_id: resp.client_order_id,
+            side: resp.side,
+            executions: resp.executions,
+            order_type: resp.order_type,
+            state: resp.state,
+            reject_reason: resp.reject_reason,
+            average_price: resp.average_price,
+            filled_asset_quantity: resp.filled_asset_quantity,
+            created_at: resp.created_at,
+            updated_at: resp.updated_at,
+            market_order_config: resp.market_order_config,
+            limit_order_config: resp.limit_order_config,
+            stop_loss_order_config: resp.stop_loss_order_config,
+            stop_limit_order_config: resp.stop_limit_order_config,
+        }
+    }
+}
+
+#[test]
+fn test_created_order_converts_to_crypto_order(){
+    let mut json = order_json("o1", "filled");
+    json["executions"] = serde_json::json!([{"effective_price": "100", "quantity": "1", "timestamp": "2024-01-01T00:00:00Z"}]);
+    json["average_price"] = "100".into();
+    json["filled_asset_quantity"] = "1".into();
+    let created: CreateCryptoOrderResponse = serde_json::from_value(json.clone()).unwrap();
+    let order = CryptoOrder::from(created.clone());
+    assert_eq!(order, serde_json::from_value::<CryptoOrder>(json).unwrap());
+    assert_eq!(CreateCryptoOrderResponse::from(order.clone()), created);
+
+    // a freshly created order feeds the same helpers as a listed one
+    assert_eq!(order.average_fill_price(), Some(Decimal::from(100)));
+    let basis = crate::account::compute_cost_basis(&[created.into()], crate::account::CostBasisMethod::Fifo);
+    assert_eq!(basis["BTC-USD"].quantity, Decimal::ONE);
+}
+
+/// Whether a failed submission may or may not have reached Robinhood.
+fn is_transient(err: &RobinError) -> bool {
+    match err {
+        RobinError::Http(_) | RobinError::Timeout(_) => true,
+        RobinError::Api { status, .. } | RobinError::UnexpectedContentType { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+/// Whether Robinhood refused an order because its `client_order_id` is
+/// already taken, e.g. by an earlier submission that did land.
+///
+/// That is a 409, or a 4xx other than 429 whose parsed `ApiError` blames the
+/// `client_order_id` field. Only a body that doesn't parse as an `ApiError`
+/// falls back to mentioning `client_order_id` anywhere, so a structured
+/// error about another field never sets off a lookup.
+fn is_duplicate_client_order_id(err: &RobinError) -> bool {
+    match err {
+        RobinError::Api { status: 409, .. } => true,
+        RobinError::Api { status: 429, .. } => false,
+        RobinError::Api { status: 400..=499, error: Some(error), .. } => {
+            error.errors.iter().any(|e| e.attr.as_deref() == Some("client_order_id"))
+        }
+        RobinError::Api { status: 400..=499, error: None, body, .. } => body.contains("client_order_id"),
+        _ => false,
+    }
+}
+
+#[test]
+fn test_is_duplicate_client_order_id(){
+    let api = |status: u16, body: &str| RobinError::api(status, body.to_string(), None);
+    let field = |attr: &str| format!(r#"{{"type":"validation_error","errors":[{{"attr":"{attr}","detail":"client_order_id c1 has already been used."}}]}}"#);
+    assert!(is_duplicate_client_order_id(&api(400, &field("client_order_id"))));
+    assert!(is_duplicate_client_order_id(&api(409, "conflict")));
+    assert!(is_duplicate_client_order_id(&api(400, "client_order_id already exists")));
+    // a structured error about another field only mentions the id in passing
+    assert!(!is_duplicate_client_order_id(&api(400, &field("asset_quantity"))));
+    assert!(!is_duplicate_client_order_id(&api(429, "client_order_id")));
+    assert!(!is_duplicate_client_order_id(&api(500, &field("client_order_id"))));
+}
+
+/// Find an order by its `client_order_id`, optionally only among `symbol`'s
+/// orders and those created at or after `created_at_start`, in
+/// `account_number` or else the client's account.
+async fn find_by_client_order_id(rh: &Robinhood, account_number: Option<&str>, symbol: Option<&str>, created_at_start: Option<&str>, client_order_id: &str) -> Result<Option<CryptoOrder>>{
+    let mut params = GetCryptoOrderParams::builder().build();
+    params.account_number = account_number.map(String::from);
+    params.symbol = symbol.map(String::from);
+    params.created_at_start = created_at_start.map(String::from);
+    let mut orders = std::pin::pin!(stream_crypto_orders(rh, params));
+    while let Some(order) = orders.try_next().await? {
+        if order.client_order_id == client_order_id {
+            return Ok(Some(order));
+        }
+    }
+    Ok(None)
+}
+
+/// How long before the first submission `create_order_idempotent` starts
+/// looking for the order, to allow for clock skew and server-side delays.
+const ORDER_LOOKUP_MARGIN: Duration = Duration::from_secs(300);
+
+/// Create an order, retrying transient failures without risking a duplicate.
+///
+/// When a submission fails with a network error, timeout, 429 or 5xx, the
+/// order may still have been placed. After each such failure, including the
+/// last, this looks for `params.client_order_id` among the orders on the same
+/// symbol, in the order's account, created since just before the first
+/// submission, and returns that order if it landed. A lookup that itself fails transiently counts as
+/// not found yet. A resubmission refused because the `client_order_id` is
+/// already taken is resolved the same way. Retries follow `rh.retry`, or
+/// `RetryConfig::default()` if none is set.
+///
+/// `params.client_order_id` must be stable for the logical order: generate
+/// it once (e.g. with `new_client_order_id`) and reuse it whenever you
+/// resubmit, including across calls to this function. In dry-run mode
+/// nothing is sent, as with `create_crypto_order`.
+pub async fn create_order_idempotent(rh: &Robinhood, params: CreateCyptoOrderParams) -> Result<CreateCryptoOrderResponse>{
+    params.validate()?;
+    if rh.dry_run {
+        create_crypto_order_dry_run(rh, &params)?;
+        return Ok(CreateCryptoOrderResponse::dry_run(params));
+    }
     let path = "/api/v1/crypto/trading/orders/";
-    let headers = rh.auth_headers(&path, "POST", &serde_json::to_string(&param).unwrap());
-    let client = Client::new();
-    let resp = client
-        .post(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .json(&param)
-        .send()
-        .await?.json::<CreateCryptoOrderResponse>().await?;
-    Ok(resp)
+    let body = order_body(rh, &params)?;
+    let retry = rh.retry.clone().unwrap_or_default();
+    // the server's clock, as `sync_time` measured it, less the margin
+    let offset = Duration::from_secs(rh.clock_offset().unsigned_abs());
+    let server_now = if rh.clock_offset() >= 0 { SystemTime::now() + offset } else { SystemTime::now() - offset };
+    let since = crate::time::rfc3339_seconds(server_now - ORDER_LOOKUP_MARGIN);
+    let find = || find_by_client_order_id(rh, params.account_number.as_deref(), Some(&params.symbol), Some(&since), &params.client_order_id);
+    let mut attempt = 0;
+    loop {
+        let err = match rh.send(Method::POST, path, &body).await.and_then(decode_response) {
+            Ok(order) => return Ok(order),
+            Err(e) => e,
+        };
+        if is_duplicate_client_order_id(&err) {
+            return match find().await {
+                Ok(Some(order)) => Ok(order.into()),
+                _ => Err(err),
+            };
+        }
+        if !is_transient(&err) {
+            return Err(err);
+        }
+        tokio::time::sleep(retry.delay(attempt, None)).await;
+        match find().await {
+            Ok(Some(order)) => return Ok(order.into()),
+            Ok(None) => {}
+            Err(lookup) if is_transient(&lookup) => {}
+            Err(lookup) => return Err(lookup),
+        }
+        if attempt >= retry.max_retries {
+            return Err(err);
+        }
+        attempt += 1;
+    }
+}
+
+/// Generate a fresh random `client_order_id`.
+///
+/// Robinhood de-duplicates orders by this id, so generate it once up front and
+/// reuse it when retrying a submission that failed with a network error.
+pub fn new_client_order_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// How many orders `create_orders` keeps in flight at once.
+pub const CREATE_ORDERS_CONCURRENCY: usize = 4;
+
+/// Submit several orders, e.g. the legs of a rebalance, and report each
+/// outcome separately.
+///
+/// Orders are sent with at most `CREATE_ORDERS_CONCURRENCY` in flight, each
+/// through `create_crypto_order`, so validation, dry-run mode and the rate
+/// limiter apply as usual. The results line up with `orders`, and a rejected
+/// order doesn't stop the others. An empty `client_order_id` is replaced
+/// with `new_client_order_id()`; the id used is echoed in each response.
+///
+/// The batch is not atomic: some orders may be placed (and fill) while
+/// others fail, so check every result and unwind or resubmit as needed.
+pub async fn create_orders(rh: &Robinhood, orders: Vec<CreateCyptoOrderParams>) -> Vec<Result<CreateCryptoOrderResponse>>{
+    futures::stream::iter(orders)
+        .map(|mut params| async move {
+            if params.client_order_id.is_empty() {
+                params.client_order_id = new_client_order_id();
+            }
+            create_crypto_order(rh, params).await
+        })
+        .buffered(CREATE_ORDERS_CONCURRENCY)
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_create_orders_reports_each_result_in_order(){
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(body_partial_json(serde_json::json!({"symbol": "DOGE-USD"})))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "type": "validation_error", "errors": [{"attr": "asset_quantity", "detail": "too small"}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(|req: &Request| {
+            let sent: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+            let mut order = order_json("o1", "open");
+            order["symbol"] = sent["symbol"].clone();
+            order["client_order_id"] = sent["client_order_id"].clone();
+            ResponseTemplate::new(201).set_body_json(order)
+        })
+        .mount(&server)
+        .await;
+
+    let order = |symbol: &str, client_order_id: &str| CreateCyptoOrderParams::builder()
+        .symbol(symbol.to_string())
+        .client_order_id(client_order_id.to_string())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig { asset_quantity: Decimal::ONE })
+        .build();
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let results = create_orders(&rh, vec![
+        order("BTC-USD", "mine"),
+        order("DOGE-USD", ""),
+        order("ETH-USD", ""),
+    ]).await;
+
+    let [btc, doge, eth] = &results[..] else { panic!("expected three results") };
+    assert_eq!(btc.as_ref().unwrap().client_order_id, "mine");
+    assert!(matches!(doge, Err(RobinError::Api { status: 400, .. })));
+    let eth = eth.as_ref().unwrap();
+    assert_eq!(eth.symbol, "ETH-USD");
+    assert!(Uuid::parse_str(&eth.client_order_id).is_ok());
+}
+
+/// Submit a market order for `asset_quantity` of `symbol`.
+async fn market_order(rh: &Robinhood, symbol: &str, side: OrderSide, asset_quantity: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    let params = CreateCyptoOrderParams::builder()
+        .symbol(symbol.to_string())
+        .client_order_id(client_order_id.map_or_else(new_client_order_id, str::to_string))
+        .side(side)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig::builder().asset_quantity(asset_quantity).build())
+        .build();
+    create_crypto_order(rh, params).await
+}
+
+/// Submit a good-till-canceled limit order.
+async fn limit_order(rh: &Robinhood, symbol: &str, side: OrderSide, asset_quantity: Decimal, limit_price: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    let params = CreateCyptoOrderParams::builder()
+        .symbol(symbol.to_string())
+        .client_order_id(client_order_id.map_or_else(new_client_order_id, str::to_string))
+        .side(side)
+        .order_type(OrderType::Limit)
+        .limit_order_config(LimitOrderConfig::builder()
+            .asset_quantity(asset_quantity)
+            .limit_price(Some(limit_price))
+            .time_in_force(Some(TimeInForce::Gtc))
+            .build())
+        .build();
+    create_crypto_order(rh, params).await
+}
+
+/// Buy `asset_quantity` of `symbol` (e.g. "BTC-USD") at market.
+///
+/// Pass `None` for `client_order_id` to have one generated; the id used is
+/// echoed back in the response. For safe retries after a network error, create
+/// the id with `new_client_order_id` first and resubmit with the same id.
+/// Use `create_crypto_order` directly for anything more advanced.
+pub async fn market_buy(rh: &Robinhood, symbol: &str, asset_quantity: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    market_order(rh, symbol, OrderSide::Buy, asset_quantity, client_order_id).await
+}
+
+/// Buy about `quote_amount` (e.g. $50) worth of `symbol` at market.
+///
+/// Robinhood's `market_order_config` only takes an `asset_quantity`, so the
+/// conversion happens client-side with `quantity_for_notional`: `quote_amount`
+/// is divided by the estimated ask (inclusive of spread) for that size from
+/// `get_estimated_price`, snapped down to the pair's `asset_increment` and
+/// checked against its size limits before the order is sent. The fill price
+/// can move between quote and fill, so the amount spent may differ slightly
+/// from `quote_amount`. Fails with `RobinError::InvalidArgument` if the pair
+/// or its quote is unavailable, if the pair isn't quoted in the account's
+/// currency (`quote_amount` is in that currency), or if the amount converts
+/// to an invalid size.
+///
+/// See `market_buy` for how `client_order_id` is handled.
+pub async fn market_buy_notional(rh: &Robinhood, symbol: &str, quote_amount: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    let asset_quantity = quantity_for_notional(rh, symbol, OrderSide::Buy, quote_amount).await?;
+    market_order(rh, symbol, OrderSide::Buy, asset_quantity, client_order_id).await
+}
+
+/// How much of `symbol` `notional` (e.g. $250) buys or sells right now.
+///
+/// The rough size from the best bid/ask is priced with `get_estimated_price`
+/// for `side` (the ask for a buy, the bid for a sell), `notional` is divided
+/// by that price, and the result is snapped down to the pair's
+/// `asset_increment`, so it never costs more than `notional` at the quoted
+/// price. `notional` is in the account's currency, so the pair must be
+/// quoted in it (e.g. `BTC-EUR` for a EUR account). Fails with
+/// `RobinError::InvalidArgument` if it isn't, if the pair or a quote is
+/// unavailable, or if the quantity falls outside the pair's size limits,
+/// e.g. below `min_order_size`.
+pub async fn quantity_for_notional(rh: &Robinhood, symbol: &str, side: OrderSide, notional: Decimal) -> Result<Decimal>{
+    let price_side = price_side_for(side)?;
+    let pair = rh.trading_pair(symbol).await?
+        .ok_or_else(|| RobinError::InvalidArgument(format!("no trading pair {symbol}")))?;
+    rh.require_account_currency(symbol, &pair.quote_code).await?;
+    let best = get_best_price_map(rh, vec![symbol]).await?
+        .remove(symbol)
+        .map(|quote| match price_side {
+            PriceSide::Ask => quote.ask_inclusive_of_buy_spread,
+            _ => quote.bid_inclusive_of_sell_spread,
+        })
+        .filter(|price| price.is_sign_positive() && !price.is_zero())
+        .ok_or_else(|| RobinError::InvalidArgument(format!("no {} price for {symbol}", price_side.as_str())))?;
+    // the estimate needs a size; price the one the best quote suggests
+    let rough = pair.round_quantity(notional / best).max(pair.min_order_size);
+    let price = estimated_side_price(rh, symbol, price_side, rough).await?;
+    quantity_at(&pair, notional, price)
+}
+
+/// The least `symbol` can be bought or sold for right now: the pair's
+/// `min_order_size` priced with `get_estimated_price` for `side` (the ask for
+/// a buy, the bid for a sell), rounded up to the pair's `quote_increment`.
+///
+/// The amount is in the pair's quote currency, e.g. dollars for `BTC-USD`.
+/// An order for less is rejected as below the minimum size. Errors from the
+/// price estimate are returned as they are rather than falling back to
+/// another price; an unknown pair or a missing quote for the side is
+/// `RobinError::InvalidArgument`.
+pub async fn min_notional(rh: &Robinhood, symbol: &str, side: OrderSide) -> Result<Decimal>{
+    let price_side = price_side_for(side)?;
+    let pair = rh.trading_pair(symbol).await?
+        .ok_or_else(|| RobinError::InvalidArgument(format!("no trading pair {symbol}")))?;
+    let price = estimated_side_price(rh, symbol, price_side, pair.min_order_size).await?;
+    Ok(round_to_multiple(pair.min_order_size * price, pair.quote_increment, RoundingStrategy::AwayFromZero))
+}
+
+/// The quote side that fills an order on `side`: a buy pays the ask, a sell gets the bid.
+fn price_side_for(side: OrderSide) -> Result<PriceSide>{
+    match side {
+        OrderSide::Buy => Ok(PriceSide::Ask),
+        OrderSide::Sell => Ok(PriceSide::Bid),
+        OrderSide::Unknown(other) => Err(RobinError::InvalidArgument(format!("unsupported order side {other}"))),
+    }
+}
+
+/// The `price_side` price `get_estimated_price` quotes for `quantity` of `symbol`.
+async fn estimated_side_price(rh: &Robinhood, symbol: &str, price_side: PriceSide, quantity: Decimal) -> Result<Decimal>{
+    get_estimated_price(rh, symbol, price_side.clone(), quantity).await?
+        .results
+        .iter()
+        .find_map(|quote| match price_side {
+            PriceSide::Ask => quote.ask(),
+            _ => quote.bid(),
+        })
+        .filter(|price| price.is_sign_positive() && !price.is_zero())
+        .ok_or_else(|| RobinError::InvalidArgument(format!("no {} price for {symbol}", price_side.as_str())))
+}
+
+/// `notional / price` snapped down to `pair`'s increment and checked against its size limits.
+fn quantity_at(pair: &TradingPairs, notional: Decimal, price: Decimal) -> Result<Decimal>{
+    let quantity = pair.round_quantity(notional / price);
+    pair.validate_order(quantity, None)
+        .map_err(|e| RobinError::InvalidArgument(format!("{notional} of {} at {price}: {e}", pair.symbol)))?;
+    Ok(quantity)
+}
+
+/// Sell `asset_quantity` of `symbol` (e.g. "BTC-USD") at market.
+///
+/// See `market_buy` for how `client_order_id` is handled.
+pub async fn market_sell(rh: &Robinhood, symbol: &str, asset_quantity: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    market_order(rh, symbol, OrderSide::Sell, asset_quantity, client_order_id).await
+}
+
+/// Place a good-till-canceled limit buy of `asset_quantity` at `limit_price`.
+///
+/// See `market_buy` for how `client_order_id` is handled.
+pub async fn limit_buy(rh: &Robinhood, symbol: &str, asset_quantity: Decimal, limit_price: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    limit_order(rh, symbol, OrderSide::Buy, asset_quantity, limit_price, client_order_id).await
+}
+
+/// Place a good-till-canceled limit sell of `asset_quantity` at `limit_price`.
+///
+/// See `market_buy` for how `client_order_id` is handled.
+pub async fn limit_sell(rh: &Robinhood, symbol: &str, asset_quantity: Decimal, limit_price: Decimal, client_order_id: Option<&str>) -> Result<CreateCryptoOrderResponse>{
+    limit_order(rh, symbol, OrderSide::Sell, asset_quantity, limit_price, client_order_id).await
+}
+
+#[tokio::test]
+async fn test_limit_sell_builds_order(){
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(body_partial_json(serde_json::json!({
+            "symbol": "XRP-USD", "side": "sell", "type": "limit",
+            "limit_order_config": {"asset_quantity": "2", "limit_price": "3.5", "time_in_force": "gtc"}
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "o1", "account_number": "1", "symbol": "XRP-USD", "client_order_id": "c1",
+            "side": "sell", "executions": [], "type": "limit", "state": "open",
+            "average_price": null, "filled_asset_quantity": null,
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+            "market_order_config": null, "limit_order_config": null,
+            "stop_loss_order_config": null, "stop_limit_order_config": null
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let price = "3.5".parse::<Decimal>().unwrap();
+    let resp = limit_sell(&rh, "XRP-USD", Decimal::from(2), price, None).await.unwrap();
+    assert_eq!(resp.state, OrderState::Open);
+    let req = &server.received_requests().await.unwrap()[0];
+    let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+    assert!(Uuid::parse_str(body["client_order_id"].as_str().unwrap()).is_ok());
+    crate::auth::assert_signed(&rh, req);
+}
+
+#[tokio::test]
+async fn test_account_number_scopes_holdings_and_orders(){
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let empty_page = serde_json::json!({"next": null, "previous": null, "results": []});
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/holdings/"))
+        .and(query_param("asset_code", "BTC"))
+        .and(query_param("account_number", "ACC2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&empty_page))
+        .expect(1)
+        .mount(&server)
+        .await;
+    for account in ["ACC2", "ACC3"] {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crypto/trading/orders/"))
+            .and(query_param("account_number", account))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&empty_page))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    let mut created = order_json("o1", "open");
+    created["account_number"] = "ACC2".into();
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(body_partial_json(serde_json::json!({"account_number": "ACC2", "symbol": "BTC-USD"})))
+        .respond_with(ResponseTemplate::new(201).set_body_json(created))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri()).with_account_number("ACC2");
+    get_crypto_holdings(&rh, vec!["BTC"]).await.unwrap();
+    get_crypto_orders(&rh, GetCryptoOrderParams::builder().build()).await.unwrap();
+    get_crypto_orders(&rh, GetCryptoOrderParams::builder().account_number("ACC3").build()).await.unwrap();
+    let order = market_buy(&rh, "BTC-USD", Decimal::ONE, None).await.unwrap();
+    assert_eq!(order.account_number, "ACC2");
+
+    // without an account the requests are unchanged
+    let plain = crate::auth::test_robinhood("http://localhost");
+    let params = CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id("c1".to_string())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig { asset_quantity: Decimal::ONE })
+        .build();
+    let body = create_crypto_order_dry_run(&plain, &params).unwrap().body.unwrap();
+    assert!(!body.contains("account_number"));
+    assert!(!orders_path(&plain, GetCryptoOrderParams::builder().build()).contains("account_number"));
+}
+
+#[tokio::test]
+async fn test_market_buy_notional_converts_at_the_estimated_ask(){
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(account_json("USD")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/trading_pairs/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": [{
+            "asset_code": "BTC", "quote_code": "USD", "quote_increment": "0.01", "asset_increment": "0.0001",
+            "max_order_size": "10", "min_order_size": "0.0001", "status": "tradable", "symbol": "BTC-USD"
+        }]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/best_bid_ask/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-USD", "price": 29990.0,
+            "bid_inclusive_of_sell_spread": 29980.0, "sell_spread": 0.001,
+            "ask_inclusive_of_buy_spread": 30000.0, "buy_spread": 0.001,
+            "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/estimated_price/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-USD", "side": "ask", "price": 33000.0, "quantity": 0.0016,
+            "ask_inclusive_of_buy_spread": 33000.0, "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(body_partial_json(serde_json::json!({"side": "buy", "type": "market", "market_order_config": {"asset_quantity": "0.0015"}})))
+        .respond_with(ResponseTemplate::new(201).set_body_json(order_json("o1", "open")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    // sized at the estimated ask for the order, not the best ask:
+    // $50 / $33,000 = 0.001515..., snapped down to 0.0015
+    market_buy_notional(&rh, "BTC-USD", Decimal::from(50), None).await.unwrap();
+    match market_buy_notional(&rh, "BTC-USD", Decimal::ONE, None).await {
+        Err(RobinError::InvalidArgument(msg)) => assert!(msg.contains("below the minimum"), "{msg}"),
+        other => panic!("expected a size error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_quantity_for_notional_prices_the_side(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(account_json("USD")))
+        .mount(&server)
+        .await;
+    let pairs = ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": [{
+        "asset_code": "BTC", "quote_code": "USD", "quote_increment": "0.01", "asset_increment": "0.0001",
+        "max_order_size": "10", "min_order_size": "0.0001", "status": "tradable", "symbol": "BTC-USD"
+    }]}));
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/trading_pairs/"))
+        .respond_with(pairs.clone())
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/best_bid_ask/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-USD", "price": 29990.0,
+            "bid_inclusive_of_sell_spread": 29980.0, "sell_spread": 0.001,
+            "ask_inclusive_of_buy_spread": 30000.0, "buy_spread": 0.001,
+            "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .mount(&server)
+        .await;
+    let estimate = |side: &str, price: f64| ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+        "symbol": "BTC-USD", "side": side, "price": price, "quantity": 0.0033,
+        "bid_inclusive_of_sell_spread": if side == "bid" { Some(price) } else { None },
+        "ask_inclusive_of_buy_spread": if side == "ask" { Some(price) } else { None },
+        "timestamp": "2025-01-01T00:00:00Z"
+    }]}));
+    for (side, price) in [("ask", 30300.0), ("bid", 29700.0)] {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crypto/marketdata/estimated_price/"))
+            .and(query_param("side", side))
+            .respond_with(estimate(side, price))
+            .mount(&server)
+            .await;
+    }
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    // $100 / $30,300 = 0.0033003..., snapped down to 0.0033
+    let buy = quantity_for_notional(&rh, "BTC-USD", OrderSide::Buy, Decimal::from(100)).await.unwrap();
+    assert_eq!(buy, "0.0033".parse::<Decimal>().unwrap());
+    // $100 / $29,700 = 0.0033670...
+    let sell = quantity_for_notional(&rh, "BTC-USD", OrderSide::Sell, Decimal::from(100)).await.unwrap();
+    assert_eq!(sell, "0.0033".parse::<Decimal>().unwrap());
+    let requests = server.received_requests().await.unwrap();
+    let estimated = requests.iter().find(|r| r.url.path().ends_with("estimated_price/")).unwrap();
+    assert!(estimated.url.query().unwrap().contains("quantity=0.0033"));
+
+    match quantity_for_notional(&rh, "BTC-USD", OrderSide::Buy, Decimal::ONE).await {
+        Err(RobinError::InvalidArgument(msg)) => assert!(msg.contains("below the minimum"), "{msg}"),
+        other => panic!("expected a size error, got {other:?}"),
+    }
+
+    // a EUR account can't spend euros on a USD pair
+    server.reset().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/accounts/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(account_json("EUR")))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/trading_pairs/"))
+        .respond_with(pairs)
+        .mount(&server)
+        .await;
+    let rh = crate::auth::test_robinhood(&server.uri());
+    match quantity_for_notional(&rh, "BTC-USD", OrderSide::Buy, Decimal::from(100)).await {
+        Err(RobinError::InvalidArgument(msg)) => assert_eq!(msg, "BTC-USD is quoted in USD, but the account's buying power is in EUR"),
+        other => panic!("expected a currency error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_min_notional_prices_the_minimum_size(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/trading_pairs/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": [{
+            "asset_code": "BTC", "quote_code": "USD", "quote_increment": "0.01", "asset_increment": "0.0001",
+            "max_order_size": "10", "min_order_size": "0.0001", "status": "tradable", "symbol": "BTC-USD"
+        }]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/estimated_price/"))
+        .and(query_param("side", "ask"))
+        .and(query_param("quantity", "0.0001"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"results": [{
+            "symbol": "BTC-USD", "side": "ask", "price": 30303.33, "quantity": 0.0001,
+            "ask_inclusive_of_buy_spread": 30303.33, "timestamp": "2025-01-01T00:00:00Z"
+        }]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/marketdata/estimated_price/"))
+        .and(query_param("side", "bid"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({"detail": "quote unavailable"})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    // 0.0001 * $30,303.33 = $3.030333, rounded up to the cent
+    let buy = min_notional(&rh, "BTC-USD", OrderSide::Buy).await.unwrap();
+    assert_eq!(buy, "3.04".parse::<Decimal>().unwrap());
+    let sell = min_notional(&rh, "BTC-USD", OrderSide::Sell).await;
+    assert!(matches!(sell, Err(RobinError::Api { status: 400, .. })), "{sell:?}");
+    assert!(matches!(min_notional(&rh, "DOGE-USD", OrderSide::Buy).await, Err(RobinError::InvalidArgument(_))));
 }
 
+#[tokio::test]
+async fn test_market_buy_uses_supplied_client_order_id(){
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let id = new_client_order_id();
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "client_order_id": id, "side": "buy", "type": "market",
+            "market_order_config": {"asset_quantity": "1"}
+        })))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    // a failed submission can be retried with the same id
+    assert!(market_buy(&rh, "BTC-USD", Decimal::from(1), Some(&id)).await.is_err());
+}
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "CancelBody")]
+#[non_exhaustive]
+/// Acknowledgement that a cancel request was accepted.
+///
+/// Cancellation is asynchronous; poll the order (e.g. `await_order_terminal`)
+/// to see it reach `canceled`. `message` is empty when the server accepted
+/// the cancel without a body (e.g. a 204).
+pub struct CancelOrderResponse {
+    pub message: String,
+}
+
+/// Shapes the cancel endpoint may answer with: a bare JSON string, or an
+/// object carrying the text under `message` or `detail`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CancelBody {
+    Text(String),
+    Object {
+        #[serde(alias = "detail")]
+        message: String,
+    },
+}
+
+impl From<CancelBody> for CancelOrderResponse {
+    fn from(body: CancelBody) -> Self {
+        match body {
+            CancelBody::Text(message) | CancelBody::Object { message } => CancelOrderResponse { message },
+        }
+    }
+}
 
 /// Attempt to cancel a crypto order by its ID.
-pub async fn cancel_crypto_order(rh: &Robinhood, id: String) -> Result<String, reqwest::Error>{
+///
+/// A rejected cancel (e.g. the order already filled) is returned as
+/// `RobinError::Api` with the server's error body. In dry-run mode nothing is
+/// sent and the cancel is reported as accepted.
+pub async fn cancel_crypto_order(rh: &Robinhood, id: String) -> Result<CancelOrderResponse>{
     let path = format!("/api/v1/crypto/trading/orders/{}/cancel/", id);
-    let headers = rh.auth_headers(&path, "POST", "");
-    let client = Client::new();
-    let resp = client
-        .post(format!("https://trading.robinhood.com{path}"))
-        .headers(headers)
-        .send()
-        .await?;
-    let body = resp.text().await?;
-    let cleaned = body.trim_matches('"').to_string();
-    Ok(cleaned)
+    if rh.dry_run {
+        return dry_run_cancel(rh, &path, &id);
+    }
+    let resp = rh.send(Method::POST, &path, "").await?;
+    decode_cancel(resp)
+}
+
+/// Cancel the order created with `client_order_id`.
+///
+/// Robinhood only cancels by its own order id and can't filter orders by
+/// `client_order_id`, so this pages through the account's orders until it
+/// finds the match, then calls `cancel_crypto_order`. Returns
+/// `RobinError::NotFound` if no order has that id and
+/// `RobinError::OrderTerminal` if it has already finished.
+pub async fn cancel_by_client_order_id(rh: &Robinhood, client_order_id: &str) -> Result<CancelOrderResponse>{
+    let order = find_by_client_order_id(rh, None, None, None, client_order_id).await?
+        .ok_or_else(|| RobinError::NotFound(format!("no order with client_order_id {client_order_id}")))?;
+    if order.state.is_terminal() {
+        return Err(RobinError::OrderTerminal(Box::new(order)));
+    }
+    cancel_crypto_order(rh, order.id).await
+}
+
+/// How often `cancel_and_confirm` re-reads the order.
+pub const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+/// Where an order ended up after `cancel_and_confirm`.
+pub enum CancelOutcome {
+    /// Canceled before anything filled.
+    Canceled(CryptoOrder),
+    /// Canceled, but part of the order filled first; see `executions` and
+    /// `filled_asset_quantity` for how much.
+    PartiallyFilled(CryptoOrder),
+    /// Filled completely; the cancel lost the race.
+    Filled(CryptoOrder),
+    /// Failed or was rejected by Robinhood instead.
+    Closed(CryptoOrder),
+}
+
+impl CancelOutcome {
+    fn from_terminal(order: CryptoOrder) -> Self {
+        let filled_any = !order.executions.is_empty()
+            || order.filled_asset_quantity.is_some_and(|q| !q.is_zero());
+        match order.state {
+            OrderState::Canceled if filled_any => CancelOutcome::PartiallyFilled(order),
+            OrderState::Canceled => CancelOutcome::Canceled(order),
+            OrderState::Filled => CancelOutcome::Filled(order),
+            _ => CancelOutcome::Closed(order),
+        }
+    }
+
+    /// The order as last read.
+    pub fn order(&self) -> &CryptoOrder {
+        match self {
+            CancelOutcome::Canceled(order)
+            | CancelOutcome::PartiallyFilled(order)
+            | CancelOutcome::Filled(order)
+            | CancelOutcome::Closed(order) => order,
+        }
+    }
+
+    /// Whether nothing filled, i.e. the order is fully off the book unexecuted.
+    pub fn is_clean_cancel(&self) -> bool {
+        matches!(self, CancelOutcome::Canceled(_))
+    }
+}
+
+/// Cancel an order and wait until Robinhood confirms where it ended up.
+///
+/// Submits the cancel, then polls `get_crypto_order` every
+/// `CANCEL_POLL_INTERVAL` until the order is terminal. A partial fill that
+/// landed before the cancel is reported as `CancelOutcome::PartiallyFilled`,
+/// and an order that filled first as `CancelOutcome::Filled`. If the cancel
+/// itself is rejected because the order already finished, that final state
+/// is returned rather than the error. Fails with `RobinError::OrderTimeout`
+/// if the order is still working after `timeout`. Dry-run mode never sends
+/// the cancel, so the order would never change; this fails there with
+/// `RobinError::InvalidArgument`.
+pub async fn cancel_and_confirm(rh: &Robinhood, id: &str, timeout: Duration) -> Result<CancelOutcome>{
+    if rh.dry_run {
+        return Err(RobinError::InvalidArgument("cancel_and_confirm can't confirm a dry-run cancel".to_string()));
+    }
+    if let Err(e) = cancel_crypto_order(rh, id.to_string()).await {
+        let RobinError::Api { .. } = e else { return Err(e) };
+        let order = get_crypto_order(rh, id).await?;
+        if !order.state.is_terminal() {
+            return Err(e);
+        }
+        return Ok(CancelOutcome::from_terminal(order));
+    }
+    let order = await_order_terminal(rh, id, CANCEL_POLL_INTERVAL, timeout).await?;
+    Ok(CancelOutcome::from_terminal(order))
+}
+
+#[tokio::test]
+async fn test_cancel_and_confirm_reports_the_race(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let cancel = || Mock::given(method("POST")).and(path("/api/v1/crypto/trading/orders/o1/cancel/"));
+    let lookup = || Mock::given(method("GET")).and(path("/api/v1/crypto/trading/orders/o1/"));
+    let mut partial = order_json("o1", "canceled");
+    partial["executions"] = serde_json::json!([{"effective_price": "100", "quantity": "0.4", "timestamp": "2024-01-01T00:00:01Z"}]);
+    partial["filled_asset_quantity"] = "0.4".into();
+    cancel().respond_with(ResponseTemplate::new(200).set_body_json("submitted")).mount(&server).await;
+    lookup().respond_with(ResponseTemplate::new(200).set_body_json(order_json("o1", "open")))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    lookup().respond_with(ResponseTemplate::new(200).set_body_json(&partial)).mount(&server).await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let outcome = cancel_and_confirm(&rh, "o1", Duration::from_secs(5)).await.unwrap();
+    assert!(matches!(&outcome, CancelOutcome::PartiallyFilled(order) if order.total_filled_quantity() == "0.4".parse::<Decimal>().unwrap()));
+    assert!(!outcome.is_clean_cancel());
+
+    // the cancel is rejected because the order filled first
+    server.reset().await;
+    cancel().respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+        "type": "validation_error", "errors": [{"attr": null, "detail": "Order is already filled"}]
+    }))).mount(&server).await;
+    lookup().respond_with(ResponseTemplate::new(200).set_body_json(order_json("o1", "filled"))).mount(&server).await;
+    let outcome = cancel_and_confirm(&rh, "o1", Duration::from_secs(5)).await.unwrap();
+    assert!(matches!(outcome, CancelOutcome::Filled(_)));
+
+    server.reset().await;
+    cancel().respond_with(ResponseTemplate::new(200).set_body_json("submitted")).mount(&server).await;
+    lookup().respond_with(ResponseTemplate::new(200).set_body_json(order_json("o1", "canceled"))).mount(&server).await;
+    let outcome = cancel_and_confirm(&rh, "o1", Duration::from_secs(5)).await.unwrap();
+    assert!(outcome.is_clean_cancel());
+    assert_eq!(outcome.order().state, OrderState::Canceled);
+}
+
+/// Decode a cancel response, treating a 2xx with an empty body as accepted.
+fn decode_cancel(resp: HttpResponse) -> Result<CancelOrderResponse>{
+    if resp.status.is_success() && resp.body.trim().is_empty() {
+        return Ok(CancelOrderResponse::default());
+    }
+    decode_response(resp)
+}
+
+/// Sign the cancel at `path` but report it as accepted without sending it.
+fn dry_run_cancel(rh: &Robinhood, path: &str, id: &str) -> Result<CancelOrderResponse>{
+    rh.signed_request(&Method::POST, path, "")?;
+    Ok(CancelOrderResponse { message: format!("dry run: cancel request for order {id} not sent") })
+}
+
+/// How many cancel requests `cancel_all_open_orders` keeps in flight at once.
+pub const CANCEL_CONCURRENCY: usize = 4;
+
+/// Cancel every working order, optionally only those for `symbol`.
+///
+/// Lists orders in the `open` and `partially_filled` states, then cancels
+/// them with at most `CANCEL_CONCURRENCY` requests in flight. Returns each
+/// order id with its own outcome so partial failures are visible; only a
+/// failure to list the orders fails the whole call.
+pub async fn cancel_all_open_orders(rh: &Robinhood, symbol: Option<&str>) -> Result<Vec<(String, Result<CancelOrderResponse>)>>{
+    let mut ids = Vec::new();
+    for state in [OrderState::Open, OrderState::PartiallyFilled] {
+        let mut params = GetCryptoOrderParams::builder().state(state).build();
+        params.symbol = symbol.map(String::from);
+        ids.extend(get_all_crypto_orders(rh, params).await?.into_iter().map(|order| order.id));
+    }
+    Ok(futures::stream::iter(ids)
+        .map(|id| async move {
+            let outcome = cancel_crypto_order(rh, id.clone()).await;
+            (id, outcome)
+        })
+        .buffered(CANCEL_CONCURRENCY)
+        .collect()
+        .await)
+}
+
+/// Synchronous versions of the trading endpoints.
+///
+/// These use a blocking HTTP client and must not be called from inside an
+/// async runtime thread; use the async functions there instead.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use super::*;
+    use crate::error::decode_blocking_response;
+
+    /// Blocking version of [`super::get_crypto_trading_pairs`].
+    pub fn get_crypto_trading_pairs(rh: &Robinhood, symbols: Vec<&str>) -> Result<CryptoTradingPairsResponse> {
+        get_crypto_trading_pairs_page(rh, symbols, PageParams::default())
+    }
+
+    /// Blocking version of [`super::get_crypto_trading_pairs_page`].
+    pub fn get_crypto_trading_pairs_page(rh: &Robinhood, symbols: Vec<&str>, page: PageParams) -> Result<CryptoTradingPairsResponse> {
+        let path = paged_path("/api/v1/crypto/trading/trading_pairs/", "symbol", &symbols, &page, None);
+        let resp = rh.send_blocking(Method::GET, &path, "")?;
+        decode_blocking_response(resp)
+    }
+
+    /// Blocking version of [`super::get_crypto_holdings`].
+    pub fn get_crypto_holdings(rh: &Robinhood, symbols: Vec<&str>) -> Result<CryptoHoldingsResponse> {
+        get_crypto_holdings_page(rh, symbols, PageParams::default())
+    }
+
+    /// Blocking version of [`super::get_crypto_holdings_page`].
+    pub fn get_crypto_holdings_page(rh: &Robinhood, symbols: Vec<&str>, page: PageParams) -> Result<CryptoHoldingsResponse> {
+        let path = paged_path("/api/v1/crypto/trading/holdings/", "asset_code", &symbols, &page, rh.account_number.as_deref());
+        let resp = rh.send_blocking(Method::GET, &path, "")?;
+        decode_blocking_response(resp)
+    }
+
+    /// Blocking version of [`super::get_crypto_orders`].
+    pub fn get_crypto_orders(rh: &Robinhood, params: GetCryptoOrderParams) -> Result<CryptoOrdersResponse> {
+        let path = orders_path(rh, params);
+        let resp = rh.send_blocking(Method::GET, &path, "")?;
+        decode_blocking_response(resp)
+    }
+
+    /// Blocking version of [`super::get_crypto_order`].
+    pub fn get_crypto_order(rh: &Robinhood, id: &str) -> Result<CryptoOrder> {
+        let path = format!("/api/v1/crypto/trading/orders/{id}/");
+        let resp = rh.send_blocking(Method::GET, &path, "")?;
+        match decode_blocking_response(resp) {
+            Err(RobinError::Api { status: 404, body, .. }) => Err(RobinError::NotFound(body)),
+            other => other,
+        }
+    }
+
+    /// Blocking version of [`super::create_crypto_order`].
+    pub fn create_crypto_order(rh: &Robinhood, param: CreateCyptoOrderParams) -> Result<CreateCryptoOrderResponse> {
+        param.validate()?;
+        if rh.dry_run {
+            create_crypto_order_dry_run(rh, &param)?;
+            return Ok(CreateCryptoOrderResponse::dry_run(param));
+        }
+        let path = "/api/v1/crypto/trading/orders/";
+        let body = order_body(rh, &param)?;
+        let resp = rh.send_blocking(Method::POST, path, &body)?;
+        decode_blocking_response(resp)
+    }
+
+    /// Blocking version of [`super::cancel_crypto_order`].
+    pub fn cancel_crypto_order(rh: &Robinhood, id: String) -> Result<CancelOrderResponse> {
+        let path = format!("/api/v1/crypto/trading/orders/{}/cancel/", id);
+        if rh.dry_run {
+            return dry_run_cancel(rh, &path, &id);
+        }
+        let resp = rh.send_blocking(Method::POST, &path, "")?;
+        let (status, headers) = (resp.status(), resp.headers().clone());
+        decode_cancel(HttpResponse { status, headers, body: resp.text()? })
+    }
 }
 
 #[tokio::test]
 async fn test_create_cancel_crypto_order(){
-    let rh = Robinhood::from_env();
+    let rh = crate::auth::cassette_robinhood("create_cancel_crypto_order");
     let resp = create_crypto_order(&rh, CreateCyptoOrderParams::builder()
         .symbol("XRP-USD".to_string())
         .client_order_id(Uuid::new_v4().to_string())
-        .order_type("limit".to_string())
-        .side("buy".to_string())
+        .order_type(OrderType::Limit)
+        .side(OrderSide::Buy)
         .limit_order_config(LimitOrderConfig::builder()
             .asset_quantity(Decimal::from(1))
             .limit_price(Option::from(Decimal::from(1)))
-            .time_in_force(Option::from("gfd".to_string())).build())
+            .time_in_force(Some(TimeInForce::Gfd)).build())
         .build()).await;
 
     let id = match resp{
         Ok(resp) => {
-            assert_eq!(resp.side, "buy");
+            assert_eq!(resp.side, OrderSide::Buy);
             assert_eq!(resp.symbol, "XRP-USD");
             resp.id
         }
@@ -413,11 +2691,12 @@ async fn test_create_cancel_crypto_order(){
         }
     };
 
-    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-    let cancel_resp = format!("Cancel request has been submitted for order {id}");
-    match cancel_crypto_order(&rh, id).await{
+    if crate::auth::recording() {
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+    }
+    match cancel_crypto_order(&rh, id.clone()).await{
         Ok(resp) => {
-            assert_eq!(resp, cancel_resp);
+            assert!(resp.message.contains(&id));
         }
         Err(e) => {
             panic!("Error with crypto orders: {}", e);
@@ -425,7 +2704,550 @@ async fn test_create_cancel_crypto_order(){
     }
 }
 
+#[test]
+fn test_cancel_order_response_shapes(){
+    let parse = |json: &str| serde_json::from_str::<CancelOrderResponse>(json).unwrap().message;
+    assert_eq!(parse(r#""Cancel request has been submitted for order abc""#), "Cancel request has been submitted for order abc");
+    assert_eq!(parse(r#"{"message": "submitted"}"#), "submitted");
+    assert_eq!(parse(r#"{"detail": "submitted"}"#), "submitted");
+    assert!(serde_json::from_str::<CancelOrderResponse>(r#"{"unexpected": 1}"#).is_err());
+}
+
+#[tokio::test]
+async fn test_cancel_crypto_order_accepts_empty_success_body(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/abc/cancel/"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/def/cancel/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("  "))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    assert_eq!(cancel_crypto_order(&rh, "abc".to_string()).await.unwrap(), CancelOrderResponse::default());
+    assert_eq!(cancel_crypto_order(&rh, "def".to_string()).await.unwrap().message, "");
+}
+
+#[tokio::test]
+async fn test_cancel_crypto_order_maps_rejection(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/abc/cancel/"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({"detail": "order already filled"})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    match cancel_crypto_order(&rh, "abc".to_string()).await {
+        Err(RobinError::Api { status: 400, body, .. }) => assert!(body.contains("already filled")),
+        other => panic!("expected api error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_all_open_orders_reports_each_outcome(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    for (state, id) in [("open", "1"), ("partially_filled", "2")] {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crypto/trading/orders/"))
+            .and(query_param("state", state))
+            .and(query_param("symbol", "BTC-USD"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "next": null, "previous": null, "results": [order_json(id, state)]
+            })))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/1/cancel/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json("Cancel request has been submitted for order 1"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/2/cancel/"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({"detail": "too late"})))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let outcomes = cancel_all_open_orders(&rh, Some("BTC-USD")).await.unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[0].0, "1");
+    assert!(outcomes[0].1.is_ok());
+    assert_eq!(outcomes[1].0, "2");
+    assert!(matches!(outcomes[1].1, Err(RobinError::Api { status: 400, .. })));
+}
+
+#[tokio::test]
+async fn test_cancel_by_client_order_id_resolves_server_id(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let order = |id: &str, client_id: &str, state: &str| {
+        let mut json = order_json(id, state);
+        json["client_order_id"] = client_id.into();
+        json
+    };
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(query_param("cursor", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": null, "previous": null, "results": [order("o2", "mine", "open"), order("o3", "done", "filled")]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": format!("{}/api/v1/crypto/trading/orders/?cursor=2", server.uri()),
+            "previous": null,
+            "results": [order("o1", "other", "open")]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/o2/cancel/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json("Cancel request has been submitted for order o2"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    assert!(cancel_by_client_order_id(&rh, "mine").await.unwrap().message.contains("o2"));
+    match cancel_by_client_order_id(&rh, "done").await {
+        Err(RobinError::OrderTerminal(order)) => assert_eq!((order.id.as_str(), order.state), ("o3", OrderState::Filled)),
+        other => panic!("expected a terminal order error, got {other:?}"),
+    }
+    assert!(matches!(cancel_by_client_order_id(&rh, "unknown").await, Err(RobinError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_create_order_idempotent_finds_landed_order(){
+    use std::time::Duration;
+    use crate::retry::RetryConfig;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let id = new_client_order_id();
+    let mut landed = order_json("o-1", "open");
+    landed["client_order_id"] = serde_json::json!(id);
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(504))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": null, "previous": null, "results": [order_json("o-0", "filled"), landed]
+        })))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri()).with_retry(RetryConfig {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+    });
+    let params = CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id(id.clone())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig { asset_quantity: Decimal::from(1) })
+        .build();
+    let order = create_order_idempotent(&rh, params).await.unwrap();
+    assert_eq!(order.id, "o-1");
+    assert_eq!(order.client_order_id, id);
+
+    // the lookup is scoped to the symbol and to orders created around now
+    let lookup = server.received_requests().await.unwrap().into_iter().find(|r| r.method.as_str() == "GET").unwrap();
+    let query: HashMap<_, _> = lookup.url.query_pairs().into_owned().collect();
+    assert_eq!(query["symbol"], "BTC-USD");
+    assert!(query["created_at_start"].ends_with('Z'), "{query:?}");
+}
+
+/// A wiremock server whose POSTs to the orders endpoint answer `submit`,
+/// whose first `failed_lookups` GETs fail with a 503 and whose later GETs
+/// list `landed`.
+#[cfg(test)]
+async fn idempotent_server(submit: wiremock::ResponseTemplate, submits: u64, failed_lookups: u64, landed: serde_json::Value) -> wiremock::MockServer {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(submit)
+        .expect(submits)
+        .mount(&server)
+        .await;
+    if failed_lookups > 0 {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crypto/trading/orders/"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(failed_lookups)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": null, "previous": null, "results": [landed]
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_create_order_idempotent_recovers_from_lookups_and_rejections(){
+    use std::time::Duration;
+    use crate::retry::RetryConfig;
+    use wiremock::ResponseTemplate;
+
+    let id = new_client_order_id();
+    let mut landed = order_json("o-1", "open");
+    landed["client_order_id"] = serde_json::json!(id);
+    let params = CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id(id.clone())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig { asset_quantity: Decimal::from(1) })
+        .build();
+    let retry = |max_retries| RetryConfig { max_retries, base_delay: Duration::from_millis(1), max_delay: Duration::from_millis(5) };
+
+    // the first lookup fails along with its own retries; that isn't fatal
+    let server = idempotent_server(ResponseTemplate::new(504), 2, 2, landed.clone()).await;
+    let rh = crate::auth::test_robinhood(&server.uri()).with_retry(retry(1));
+    assert_eq!(create_order_idempotent(&rh, params.clone()).await.unwrap().id, "o-1");
+
+    // out of retries, the last submission is still looked for
+    let server = idempotent_server(ResponseTemplate::new(504), 1, 0, landed.clone()).await;
+    let rh = crate::auth::test_robinhood(&server.uri()).with_retry(retry(0));
+    assert_eq!(create_order_idempotent(&rh, params.clone()).await.unwrap().id, "o-1");
+
+    // a resubmission refused as a duplicate resolves to the existing order
+    let duplicate = ResponseTemplate::new(400).set_body_json(serde_json::json!({
+        "type": "validation_error", "errors": [{"attr": "client_order_id", "detail": "An order with this client_order_id already exists."}]
+    }));
+    let server = idempotent_server(duplicate, 1, 0, landed).await;
+    let rh = crate::auth::test_robinhood(&server.uri()).with_retry(retry(2));
+    assert_eq!(create_order_idempotent(&rh, params.clone()).await.unwrap().id, "o-1");
+
+    // ... but an id that can't be found leaves the rejection as it was
+    let taken = ResponseTemplate::new(400).set_body_json(serde_json::json!({
+        "type": "validation_error", "errors": [{"attr": "client_order_id", "detail": "An order with this client_order_id already exists."}]
+    }));
+    let server = idempotent_server(taken, 1, 0, order_json("o-2", "filled")).await;
+    let rh = crate::auth::test_robinhood(&server.uri()).with_retry(retry(2));
+    assert!(matches!(create_order_idempotent(&rh, params).await, Err(RobinError::Api { status: 400, .. })));
+}
+
+#[tokio::test]
+async fn test_create_order_idempotent_looks_in_the_orders_account(){
+    use std::time::Duration;
+    use crate::retry::RetryConfig;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let id = new_client_order_id();
+    let mut landed = order_json("o-1", "open");
+    landed["client_order_id"] = serde_json::json!(id);
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(504))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .and(query_param("account_number", "OTHER"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "next": null, "previous": null, "results": [landed]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri())
+        .with_account_number("MAIN")
+        .with_retry(RetryConfig { max_retries: 0, base_delay: Duration::from_millis(1), max_delay: Duration::from_millis(5) });
+    let params = CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id(id.clone())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig { asset_quantity: Decimal::from(1) })
+        .account_number("OTHER")
+        .build();
+    assert_eq!(create_order_idempotent(&rh, params).await.unwrap().id, "o-1");
+}
+
+#[tokio::test]
+async fn test_create_order_idempotent_sends_nothing_in_dry_run(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(order_json("o-1", "open")))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri()).with_dry_run(true);
+    let params = CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id("c1".to_string())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig { asset_quantity: Decimal::ONE })
+        .build();
+    let order = create_order_idempotent(&rh, params).await.unwrap();
+    assert!(order.id.starts_with("dry-run-"));
+    assert_eq!((order.client_order_id.as_str(), order.state), ("c1", OrderState::Open));
+}
+
+#[tokio::test]
+async fn test_create_crypto_order_surfaces_rejection_reason(){
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/crypto/trading/orders/"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "type": "validation_error",
+            "errors": [{"attr": "asset_quantity", "detail": "Order quantity is too small."}]
+        })))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    match market_buy(&rh, "BTC-USD", Decimal::new(1, 12), None).await {
+        Err(RobinError::Api { status: 400, error: Some(error), .. }) => {
+            assert_eq!(error.errors[0].detail, "Order quantity is too small.");
+        }
+        other => panic!("expected parsed rejection, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_get_crypto_holdings_partial_lists_missing_codes(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": [
+            {"account_number": "1", "asset_code": "BTC", "total_quantity": 1.0, "quantity_available_for_trading": 1.0}
+        ]})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let partial = get_crypto_holdings_partial(&rh, vec!["DOGE", "BTC", "ETH", "DOGE"]).await.unwrap();
+    assert_eq!(partial.results[0].asset_code, "BTC");
+    assert_eq!(partial.missing, vec!["DOGE".to_string(), "ETH".to_string()]);
+    assert!(!partial.is_complete());
+}
 
+#[tokio::test]
+async fn test_get_crypto_trading_pair(){
+    use wiremock::matchers::{method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let pair = |symbol: &str, status: &str| serde_json::json!({
+        "asset_code": "X", "quote_code": "USD", "quote_increment": "0.01", "asset_increment": "0.01",
+        "max_order_size": "100", "min_order_size": "0.01", "status": status, "symbol": symbol
+    });
+    let server = MockServer::start().await;
+    for (symbol, results) in [
+        ("BTC-USD", vec![pair("BTC-USD", "tradable")]),
+        ("OLD-USD", vec![pair("OLD-USD", "untradable")]),
+        ("NOPE-USD", vec![]),
+    ] {
+        Mock::given(method("GET"))
+            .and(query_param("symbol", symbol))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": results})))
+            .mount(&server)
+            .await;
+    }
 
+    let rh = crate::auth::test_robinhood(&server.uri());
+    assert_eq!(get_crypto_trading_pair(&rh, "BTC-USD").await.unwrap().unwrap().symbol, "BTC-USD");
+    assert!(get_crypto_trading_pair(&rh, "OLD-USD").await.unwrap().is_none());
+    assert!(get_crypto_trading_pair(&rh, "NOPE-USD").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_trading_pair_is_cached_until_ttl(){
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"next": null, "previous": null, "results": [{
+            "asset_code": "BTC", "quote_code": "USD", "quote_increment": "0.01", "asset_increment": "0.00000001",
+            "max_order_size": "20", "min_order_size": "0.000001", "status": "tradable", "symbol": "BTC-USD"
+        }]})))
+        .mount(&server)
+        .await;
+    let requests = || async { server.received_requests().await.unwrap().len() };
 
+    let rh = crate::auth::test_robinhood(&server.uri());
+    for _ in 0..3 {
+        let pair = rh.trading_pair("BTC-USD").await.unwrap().unwrap();
+        assert!(pair.validate_order(Decimal::new(1, 2), None).is_ok());
+    }
+    assert_eq!(requests().await, 1);
+    rh.clear_pair_cache();
+    rh.trading_pair("BTC-USD").await.unwrap();
+    assert_eq!(requests().await, 2);
+
+    let uncached = crate::auth::test_robinhood(&server.uri()).with_pair_cache_ttl(Duration::ZERO);
+    uncached.trading_pair("BTC-USD").await.unwrap();
+    uncached.trading_pair("BTC-USD").await.unwrap();
+    assert_eq!(requests().await, 4);
+}
+
+#[tokio::test]
+async fn test_get_all_holdings_and_pairs_follow_cursor(){
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let holding = |asset: &str| serde_json::json!({
+        "account_number": "1", "asset_code": asset, "total_quantity": 1, "quantity_available_for_trading": 1
+    });
+    let pair = |symbol: &str| serde_json::json!({
+        "asset_code": "X", "quote_code": "USD", "quote_increment": "0.01", "asset_increment": "0.01",
+        "max_order_size": "100", "min_order_size": "0.01", "status": "tradable", "symbol": symbol
+    });
+    for (endpoint, first, second) in [
+        ("/api/v1/crypto/trading/holdings/", holding("BTC"), holding("ETH")),
+        ("/api/v1/crypto/trading/trading_pairs/", pair("BTC-USD"), pair("ETH-USD")),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("cursor", "p2"))
+            .and(query_param("limit", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "next": null, "previous": null, "results": [second]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "next": format!("{}{endpoint}?cursor=p2&limit=1", server.uri()), "previous": null, "results": [first]
+            })))
+            .mount(&server)
+            .await;
+    }
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let page = PageParams::builder().limit(1u32).build();
+    let holdings = get_all_crypto_holdings(&rh, vec![], page.clone()).await.unwrap();
+    let assets: Vec<_> = holdings.iter().map(|h| h.asset_code.as_str()).collect();
+    assert_eq!(assets, ["BTC", "ETH"]);
+    let pairs = get_all_crypto_trading_pairs(&rh, vec![], page).await.unwrap();
+    let symbols: Vec<_> = pairs.iter().map(|p| p.symbol.as_str()).collect();
+    assert_eq!(symbols, ["BTC-USD", "ETH-USD"]);
+
+    let first = &server.received_requests().await.unwrap()[0];
+    assert_eq!(first.url.query(), Some("limit=1"));
+}
+
+#[tokio::test]
+async fn test_dry_run_signs_orders_without_sending(){
+    use wiremock::MockServer;
+
+    let server = MockServer::start().await;
+    let rh = crate::auth::test_robinhood(&server.uri()).with_dry_run(true);
+    let params = CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id("c1".to_string())
+        .side(OrderSide::Buy)
+        .order_type(OrderType::Market)
+        .market_order_config(MarketOrderConfig::builder().asset_quantity(Decimal::new(1, 3)).build())
+        .build();
+
+    let req = create_crypto_order_dry_run(&rh, &params).unwrap();
+    assert_eq!(req.url, format!("{}/api/v1/crypto/trading/orders/", server.uri()));
+    assert_eq!(req.body.as_deref(), Some(serde_json::to_string(&params).unwrap().as_str()));
+    assert!(req.headers.contains_key("x-signature"));
+
+    let order = create_crypto_order(&rh, params.clone()).await.unwrap();
+    assert!(order.id.starts_with("dry-run-"));
+    assert_eq!((order.client_order_id.as_str(), order.state), ("c1", OrderState::Open));
+    assert_eq!(order.market_order_config, params.market_order_config);
+    let cancel = cancel_crypto_order(&rh, order.id.clone()).await.unwrap();
+    assert!(cancel.message.contains(&order.id));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_create_crypto_order_validates_params_locally(){
+    use wiremock::MockServer;
+
+    let params = |order_type: OrderType| CreateCyptoOrderParams::builder()
+        .symbol("BTC-USD".to_string())
+        .client_order_id("c1".to_string())
+        .side(OrderSide::Buy)
+        .order_type(order_type);
+    let limit = LimitOrderConfig::builder().asset_quantity(Decimal::ONE).limit_price(Some(Decimal::ONE)).time_in_force(None).build();
+    let stop_limit = StopLimitOrderConfig::builder().quote_amount(Decimal::TEN).limit_price(Some(Decimal::ONE)).stop_price(None).time_in_force(None).build();
+
+    assert_eq!(params(OrderType::Limit).limit_order_config(limit.clone()).build().validate(), Ok(()));
+    let mismatch = params(OrderType::Limit)
+        .market_order_config(MarketOrderConfig::builder().asset_quantity(Decimal::ONE).build())
+        .build();
+    assert_eq!(mismatch.validate().unwrap_err().to_string(), "limit order cannot use market_order_config");
+    let no_price = params(OrderType::Limit).limit_order_config(LimitOrderConfig { limit_price: None, ..limit.clone() }).build();
+    assert_eq!(no_price.validate().unwrap_err().to_string(), "limit order requires limit_price");
+    let no_quantity = params(OrderType::Limit).limit_order_config(LimitOrderConfig { asset_quantity: None, ..limit.clone() }).build();
+    assert_eq!(
+        no_quantity.validate(),
+        Err(OrderValidationError::MissingField { order_type: OrderType::Limit, field: "asset_quantity or quote_amount" })
+    );
+    let no_stop = params(OrderType::StopLimit).stop_limit_order_config(stop_limit).build();
+    assert_eq!(no_stop.validate().unwrap_err().to_string(), "stop_limit order requires stop_price");
+    assert_eq!(
+        params(OrderType::Market).build().validate().unwrap_err().to_string(),
+        "market order requires market_order_config"
+    );
+    let both = params(OrderType::Limit)
+        .limit_order_config(limit.clone())
+        .stop_loss_order_config(StopLossOrderConfig::default())
+        .build();
+    assert_eq!(both.validate(), Err(OrderValidationError::MultipleConfigs));
+
+    let server = MockServer::start().await;
+    let rh = crate::auth::test_robinhood(&server.uri());
+    match create_crypto_order(&rh, no_price).await {
+        Err(RobinError::InvalidOrder(OrderValidationError::MissingField { field: "limit_price", .. })) => {}
+        other => panic!("expected local validation error, got {other:?}"),
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
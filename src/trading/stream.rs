@@ -0,0 +1,185 @@
+//! Order state changes as a `Stream`.
+//!
+//! Like market data, Robinhood's crypto trading API has no WebSocket or other
+//! push channel for orders, so the subscription polls the order list for
+//! orders updated since the last change it saw and yields the ones whose
+//! state or fills moved. Callers consume it like a push feed; if a streaming
+//! endpoint becomes available it can back the same API.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use futures::stream::{self, Stream};
+use crate::auth::Robinhood;
+use crate::backoff::Backoff;
+use crate::error::Result;
+use crate::market_data::stream::DEFAULT_POLL_INTERVAL;
+use crate::time::Timestamp;
+use super::{get_all_crypto_orders, get_crypto_orders, CryptoOrder, GetCryptoOrderParams, OrderState};
+
+/// What an update must change for the order to be yielded again.
+type Version = (OrderState, Timestamp, usize);
+
+fn version(order: &CryptoOrder) -> Version {
+    (order.state.clone(), order.updated_at.to_owned(), order.executions.len())
+}
+
+/// `ts` truncated to whole seconds, as an `updated_at_start` filter. Never
+/// later than `ts`, so the filter can't skip an order updated at `ts`.
+#[cfg(feature = "chrono")]
+fn watermark(ts: &Timestamp) -> String {
+    ts.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// `ts` truncated to whole seconds, as an `updated_at_start` filter. Never
+/// later than `ts`, so the filter can't skip an order updated at `ts`. Only
+/// UTC (`Z`) timestamps are truncated; one with an offset is sent unchanged,
+/// since rewriting it as `Z` would move it by that offset.
+#[cfg(not(feature = "chrono"))]
+fn watermark(ts: &Timestamp) -> String {
+    match ts.strip_suffix('Z').and_then(|utc| utc.get(..19)) {
+        Some(seconds) => format!("{seconds}Z"),
+        None => ts.clone(),
+    }
+}
+
+/// Subscribe to changes to the account's orders, checking once a second.
+///
+/// See `subscribe_order_updates_with_interval`.
+pub fn subscribe_order_updates(rh: &Robinhood) -> impl Stream<Item = Result<CryptoOrder>> + '_ {
+    subscribe_order_updates_with_interval(rh, DEFAULT_POLL_INTERVAL)
+}
+
+/// Subscribe to changes to the account's orders, checking every `interval`.
+///
+/// The first poll reads the most recent page of orders as a baseline and
+/// yields nothing. After that, every order whose state, fills or
+/// `updated_at` changes is yielded once per change, oldest update first:
+/// new orders, partial fills, fills, cancels and rejections alike. Each poll
+/// asks only for orders updated since the newest change already seen, so
+/// after a failed poll (yielded as an `Err`, followed by a jittered
+/// exponential backoff) the next successful one catches up on everything
+/// missed in between. The stream ends when dropped or when the client is
+/// shut down.
+pub fn subscribe_order_updates_with_interval(rh: &Robinhood, interval: Duration) -> impl Stream<Item = Result<CryptoOrder>> + '_ {
+    let backoff = Backoff::default();
+    let state = (VecDeque::new(), HashMap::<String, Version>::new(), None::<String>, None::<u32>, true);
+    stream::unfold(state, move |(mut buffer, mut seen, mut since, mut failures, mut first)| {
+        let backoff = failures.map(|attempt| backoff.delay(attempt));
+        async move {
+            loop {
+                if let Some(order) = buffer.pop_front() {
+                    return Some((Ok(order), (buffer, seen, since, failures, first)));
+                }
+                if rh.is_shutting_down() {
+                    return None;
+                }
+                let wait = match backoff {
+                    Some(delay) if failures.is_some() => delay,
+                    _ if first => Duration::ZERO,
+                    _ => interval,
+                };
+                tokio::time::sleep(wait).await;
+
+                let orders = match &since {
+                    Some(start) => {
+                        let params = GetCryptoOrderParams::builder().updated_at_start(start.clone()).build();
+                        get_all_crypto_orders(rh, params).await
+                    }
+                    None => get_crypto_orders(rh, GetCryptoOrderParams::builder().build()).await.map(|page| page.results),
+                };
+                let mut orders = match orders {
+                    Ok(orders) => orders,
+                    Err(e) => {
+                        failures = Some(failures.map_or(0, |n| n.saturating_add(1)));
+                        return Some((Err(e), (buffer, seen, since, failures, first)));
+                    }
+                };
+                failures = None;
+                orders.sort_by_key(|order| order.updated_at.to_owned());
+                for order in orders {
+                    let mark = watermark(&order.updated_at);
+                    if since.as_ref().is_none_or(|since| mark > *since) {
+                        since = Some(mark);
+                    }
+                    if seen.get(&order.id) != Some(&version(&order)) {
+                        seen.insert(order.id.clone(), version(&order));
+                        if !first {
+                            buffer.push_back(order);
+                        }
+                    }
+                }
+                // orders last updated before the filter can only come back by changing
+                if let Some(since) = &since {
+                    seen.retain(|_, (_, updated_at, _)| watermark(updated_at) >= *since);
+                }
+                first = false;
+            }
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_subscribe_order_updates_yields_changes_once(){
+    use futures::StreamExt;
+    use wiremock::matchers::{method, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let order = |id: &str, state: &str, updated_at: &str| {
+        let mut order = super::order_json(id, state);
+        order["updated_at"] = updated_at.into();
+        order
+    };
+    let page = |orders: Vec<serde_json::Value>| ResponseTemplate::new(200)
+        .set_body_json(serde_json::json!({"next": null, "previous": null, "results": orders}));
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param_is_missing("updated_at_start"))
+        .respond_with(page(vec![order("o1", "open", "2024-01-01T00:00:01Z")]))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("updated_at_start", "2024-01-01T00:00:01Z"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("updated_at_start", "2024-01-01T00:00:01Z"))
+        .respond_with(page(vec![
+            order("o1", "canceled", "2024-01-01T00:00:05.250Z"),
+            order("o2", "filled", "2024-01-01T00:00:03.5Z"),
+            order("o1", "open", "2024-01-01T00:00:01Z"),
+        ]))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("updated_at_start", "2024-01-01T00:00:05Z"))
+        .respond_with(page(vec![order("o1", "canceled", "2024-01-01T00:00:05.250Z")]))
+        .mount(&server)
+        .await;
+
+    let rh = crate::auth::test_robinhood(&server.uri());
+    let mut updates = Box::pin(subscribe_order_updates_with_interval(&rh, Duration::from_millis(10)));
+    assert!(updates.next().await.unwrap().is_err());
+    let filled = updates.next().await.unwrap().unwrap();
+    assert_eq!((filled.id.as_str(), &filled.state), ("o2", &OrderState::Filled));
+    let canceled = updates.next().await.unwrap().unwrap();
+    assert_eq!((canceled.id.as_str(), &canceled.state), ("o1", &OrderState::Canceled));
+    // later polls return the same orders, which are not yielded again
+    assert!(tokio::time::timeout(Duration::from_millis(100), updates.next()).await.is_err());
+    // the interrupted poll may still hold a request in flight
+    drop(updates);
+
+    rh.shutdown().await;
+    let mut after = std::pin::pin!(subscribe_order_updates(&rh));
+    assert!(after.next().await.is_none());
+}
+
+#[cfg(not(feature = "chrono"))]
+#[test]
+fn test_watermark_keeps_offsets() {
+    assert_eq!(watermark(&"2024-01-01T00:00:05.123456Z".to_string()), "2024-01-01T00:00:05Z");
+    assert_eq!(watermark(&"2024-01-01T00:00:05Z".to_string()), "2024-01-01T00:00:05Z");
+    // truncating to `05:00:05Z` would be five hours after the real instant
+    assert_eq!(watermark(&"2024-01-01T05:00:05.5+05:00".to_string()), "2024-01-01T05:00:05.5+05:00");
+}
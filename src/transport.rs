@@ -0,0 +1,259 @@
+//! Pluggable HTTP layer for async requests.
+//!
+//! `Robinhood` signs each request and hands it to a `Transport` to deliver.
+//! The default, `ReqwestTransport`, sends it over the network; swap in a
+//! `MockTransport` with `Robinhood::with_transport` to exercise endpoint
+//! code (query building, signing, response parsing) without a server, or a
+//! `CassetteTransport` to record real responses once and replay them later.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::Result;
+
+/// A fully signed request, ready to send.
+#[derive(Debug, Clone)]
+pub struct SignedRequest {
+    pub method: Method,
+    /// Base URL plus path and query, exactly as signed.
+    pub url: String,
+    /// Auth headers, plus `content-type` when there is a body.
+    pub headers: HeaderMap,
+    /// JSON body, sent verbatim.
+    pub body: Option<String>,
+    /// Limit on the whole request, from connecting to reading the body.
+    pub timeout: Duration,
+}
+
+impl SignedRequest {
+    /// Turn this into a `reqwest` request on `client`, ready to `.send()`.
+    pub fn into_reqwest(self, client: &Client) -> reqwest::RequestBuilder {
+        let builder = client
+            .request(self.method, self.url)
+            .timeout(self.timeout)
+            .headers(self.headers);
+        match self.body {
+            Some(body) => builder.body(body),
+            None => builder,
+        }
+    }
+}
+
+/// A response with its body already read.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// Header Robinhood uses to identify a response; quote it in support tickets.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+impl HttpResponse {
+    /// The response's `x-request-id`, if it sent one.
+    pub fn request_id(&self) -> Option<&str> {
+        request_id(&self.headers)
+    }
+}
+
+/// Read `REQUEST_ID_HEADER` from `headers`.
+pub(crate) fn request_id(headers: &HeaderMap) -> Option<&str> {
+    headers.get(REQUEST_ID_HEADER)?.to_str().ok()
+}
+
+/// Delivers signed requests. Implementations must be `Send + Sync` so a
+/// `Robinhood` can be shared across tasks.
+pub trait Transport: Send + Sync {
+    /// Send `req` and read the full response body.
+    fn send(&self, req: SignedRequest) -> BoxFuture<'_, Result<HttpResponse>>;
+}
+
+/// The default transport, backed by a pooled `reqwest::Client`.
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, req: SignedRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let resp = req.into_reqwest(&self.client).send().await?;
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let body = resp.text().await?;
+            Ok(HttpResponse { status, headers, body })
+        })
+    }
+}
+
+/// An in-memory transport for tests: replies with queued responses in order
+/// and records every request it was given.
+#[derive(Default)]
+pub struct MockTransport {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<SignedRequest>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response with `status` and a JSON `body`.
+    pub fn push_response(&self, status: u16, body: impl Into<String>) {
+        self.responses.lock().unwrap().push_back(HttpResponse {
+            status: StatusCode::from_u16(status).expect("invalid status code"),
+            headers: HeaderMap::new(),
+            body: body.into(),
+        });
+    }
+
+    /// Every request sent so far, oldest first.
+    pub fn requests(&self) -> Vec<SignedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for MockTransport {
+    /// Panics if no response is queued, so a test fails at the unexpected call.
+    fn send(&self, req: SignedRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        let resp = self.responses.lock().unwrap().pop_front()
+            .unwrap_or_else(|| panic!("MockTransport has no response queued for {} {}", req.method, req.url));
+        self.requests.lock().unwrap().push(req);
+        Box::pin(async move { Ok(resp) })
+    }
+}
+
+/// One recorded request and the response it got.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    /// Path and query, without the base URL.
+    path: String,
+    /// Kept for reading the cassette; not matched on replay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    status: u16,
+    /// The response body, when it was JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+    /// The response body verbatim, when it wasn't.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_text: Option<String>,
+}
+
+enum CassetteMode {
+    Record(Client),
+    Replay,
+}
+
+/// A record/replay transport for tests against the real API.
+///
+/// In record mode every request goes over the network and the exchange is
+/// appended to a JSON cassette file, which is rewritten after each response.
+/// In replay mode the cassette is loaded up front and its responses are
+/// returned in order; each request must have the same method and path as the
+/// recorded one, but bodies are not compared so random client order ids
+/// still replay. Only the method, path, request body, status and response
+/// body are stored, never auth headers, but responses do include account
+/// numbers and balances, so review a cassette before committing it.
+pub struct CassetteTransport {
+    path: PathBuf,
+    mode: CassetteMode,
+    interactions: Mutex<VecDeque<Interaction>>,
+}
+
+impl CassetteTransport {
+    /// Send requests with `client` and write them to `path`, replacing any
+    /// existing cassette.
+    pub fn record(client: Client, path: impl Into<PathBuf>) -> Self {
+        CassetteTransport { path: path.into(), mode: CassetteMode::Record(client), interactions: Mutex::default() }
+    }
+
+    /// Answer requests from the cassette at `path`.
+    ///
+    /// Panics if the file is missing or malformed.
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let json = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("cannot read cassette {}: {e} (record it with RECORD=1)", path.display()));
+        let interactions = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("cannot parse cassette {}: {e}", path.display()));
+        CassetteTransport { path, mode: CassetteMode::Replay, interactions: Mutex::new(interactions) }
+    }
+
+    fn save(&self) {
+        let interactions = self.interactions.lock().unwrap();
+        let json = serde_json::to_string_pretty(&*interactions).expect("cassette serializes");
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).unwrap_or_else(|e| panic!("cannot create {}: {e}", dir.display()));
+        }
+        std::fs::write(&self.path, json + "\n")
+            .unwrap_or_else(|e| panic!("cannot write cassette {}: {e}", self.path.display()));
+    }
+}
+
+/// `url` without its scheme and host.
+fn path_of(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.find('/').map_or("/", |i| &rest[i..])
+}
+
+impl Transport for CassetteTransport {
+    /// Panics on replay if the next recorded request doesn't match `req`.
+    fn send(&self, req: SignedRequest) -> BoxFuture<'_, Result<HttpResponse>> {
+        Box::pin(async move {
+            let method = req.method.to_string();
+            let path = path_of(&req.url).to_string();
+            match &self.mode {
+                CassetteMode::Record(client) => {
+                    let request_body = req.body.clone();
+                    let resp = ReqwestTransport::new(client.clone()).send(req).await?;
+                    let (body, body_text) = match serde_json::from_str(&resp.body) {
+                        Ok(json) => (Some(json), None),
+                        Err(_) => (None, Some(resp.body.clone())),
+                    };
+                    self.interactions.lock().unwrap().push_back(Interaction {
+                        method, path, request_body, status: resp.status.as_u16(), body, body_text,
+                    });
+                    self.save();
+                    Ok(resp)
+                }
+                CassetteMode::Replay => {
+                    let next = self.interactions.lock().unwrap().pop_front()
+                        .unwrap_or_else(|| panic!("cassette {} has no more responses for {method} {path}", self.path.display()));
+                    assert_eq!((next.method.as_str(), next.path.as_str()), (method.as_str(), path.as_str()),
+                        "request does not match cassette {}", self.path.display());
+                    let body = match (next.body, next.body_text) {
+                        (Some(json), _) => json.to_string(),
+                        (None, text) => text.unwrap_or_default(),
+                    };
+                    Ok(HttpResponse {
+                        status: StatusCode::from_u16(next.status).expect("invalid status code in cassette"),
+                        headers: HeaderMap::new(),
+                        body,
+                    })
+                }
+            }
+        })
+    }
+}
+
+#[test]
+fn test_path_of_strips_origin() {
+    assert_eq!(path_of("https://trading.robinhood.com/api/v1/x/?a=1"), "/api/v1/x/?a=1");
+    assert_eq!(path_of("http://localhost:8080"), "/");
+}
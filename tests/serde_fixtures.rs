@@ -0,0 +1,198 @@
+//! Offline checks of the response data model against captured payloads.
+//!
+//! Each file in `tests/fixtures` is a response body in the shape Robinhood
+//! sends. Every test decodes one, asserts the interesting fields, then
+//! re-serializes it and checks that decoding the output gives the same value,
+//! so both directions of every serde adapter are exercised without
+//! credentials or network access.
+
+use std::fmt::Debug;
+use robinrust::account::{AccountInfo, AccountStatus, Currency};
+use robinrust::market_data::{BestPriceResponse, EstimatedPriceResponse, PriceSide};
+use robinrust::trading::{
+    CancelOrderResponse, CreateCryptoOrderResponse, CryptoHoldingsResponse, CryptoOrder, CryptoOrdersResponse,
+    CryptoTradingPairsResponse, Cursor, OrderSide, OrderState, OrderType, TimeInForce,
+};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn d(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+/// Decode `tests/fixtures/{name}` and check it survives a serialize/deserialize round trip.
+fn fixture<T: DeserializeOwned + Serialize + PartialEq + Debug>(name: &str) -> T {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("cannot read {path}: {e}"));
+    let value: T = serde_json::from_str(&json).unwrap_or_else(|e| panic!("cannot decode {name}: {e}"));
+    let reencoded = serde_json::to_string(&value).unwrap();
+    let round_trip: T = serde_json::from_str(&reencoded).unwrap_or_else(|e| panic!("cannot decode re-encoded {name}: {e}\n{reencoded}"));
+    assert_eq!(round_trip, value, "{name} changed after a round trip");
+    value
+}
+
+#[test]
+fn test_account_fixture() {
+    let account: AccountInfo = fixture("account.json");
+    assert_eq!(account.account_number, "5QR24141RQ");
+    assert_eq!(account.status, AccountStatus::Active);
+    assert_eq!(account.buying_power, d("1523.47"));
+    assert_eq!(account.buying_power_currency, Currency::Usd);
+
+    // buying power goes back out as a string, like Robinhood sends it
+    let json = serde_json::to_value(&account).unwrap();
+    assert_eq!(json["buying_power"], "1523.47");
+    assert_eq!(json["status"], "active");
+}
+
+#[test]
+fn test_best_bid_ask_fixture() {
+    let resp: BestPriceResponse = fixture("best_bid_ask.json");
+    assert_eq!(resp.results.len(), 2);
+
+    // numbers and numeric strings decode to the same kind of value
+    let btc = &resp.results[0];
+    assert_eq!(btc.symbol, "BTC-USD");
+    assert_eq!(btc.price, d("64012.5"));
+    assert_eq!(btc.bid_inclusive_of_sell_spread, d("63853.47"));
+    assert_eq!(btc.ask_inclusive_of_buy_spread, d("64172.53"));
+    assert_eq!(btc.spread(), d("319.06"));
+
+    let doge = &resp.results[1];
+    assert_eq!(doge.symbol, "DOGE-USD");
+    assert_eq!(doge.price, d("0.159812"));
+    assert_eq!(doge.sell_spread, d("0.0025"));
+    assert_eq!(doge.mid_price(), d("0.159812"));
+}
+
+#[test]
+fn test_estimated_price_fixture() {
+    let resp: EstimatedPriceResponse = fixture("estimated_price.json");
+    let [bid, ask] = &resp.results[..] else { panic!("expected two quotes") };
+
+    assert_eq!(bid.side, PriceSide::Bid);
+    assert_eq!(bid.quantity, d("0.5"));
+    assert_eq!(bid.bid_inclusive_of_sell_spread, Some(d("3751.63")));
+    assert_eq!(bid.ask_inclusive_of_buy_spread, None);
+    assert_eq!(bid.buy_spread, None);
+
+    // the other side's fields are simply left out here rather than null
+    assert_eq!(ask.side, PriceSide::Ask);
+    assert_eq!(ask.ask_inclusive_of_buy_spread, Some(d("3771.52")));
+    assert_eq!(ask.bid_inclusive_of_sell_spread, None);
+    assert_eq!(resp.spread(d("0.5")), Some(d("19.89")));
+}
+
+#[test]
+fn test_trading_pairs_fixture() {
+    let resp: CryptoTradingPairsResponse = fixture("trading_pairs.json");
+    assert_eq!(resp.next, Some(Cursor::new("cD0yMDI0LTA2")));
+    assert_eq!(resp.previous, None);
+
+    let symbols: Vec<&str> = resp.results.iter().map(|p| p.symbol.as_str()).collect();
+    assert_eq!(symbols, ["BTC-USD", "DOGE-USD", "XTZ-USD"]);
+
+    let btc = &resp.results[0];
+    assert_eq!((btc.asset_code.as_str(), btc.quote_code.as_str()), ("BTC", "USD"));
+    assert_eq!(btc.quote_increment, d("0.01"));
+    assert_eq!(btc.asset_increment, d("0.00000001"));
+    assert_eq!(btc.max_order_size, d("20"));
+    assert_eq!(btc.min_order_size, d("0.000001"));
+
+    let doge = &resp.results[1];
+    assert_eq!(doge.quote_increment, d("0.000001"));
+    assert_eq!(doge.asset_increment, Decimal::ONE);
+    assert_eq!(resp.results[2].status, "untradable");
+}
+
+#[test]
+fn test_holdings_fixture() {
+    let resp: CryptoHoldingsResponse = fixture("holdings.json");
+    assert_eq!((resp.next.as_ref(), resp.previous.as_ref()), (None, None));
+    assert_eq!(resp.results.len(), 3);
+
+    let btc = resp.find("BTC").unwrap();
+    assert_eq!(btc.account_number, "5QR24141RQ");
+    assert_eq!(btc.total_quantity, d("0.01534002"));
+    assert_eq!(btc.quantity_available_for_trading, d("0.01034002"));
+    assert_eq!(btc.locked_quantity(), d("0.005"));
+
+    assert_eq!(resp.find("DOGE").unwrap().total_quantity, d("1200"));
+    let held: Vec<&str> = resp.nonzero().map(|h| h.asset_code.as_str()).collect();
+    assert_eq!(held, ["BTC", "DOGE"]);
+}
+
+#[test]
+fn test_orders_fixture() {
+    let resp: CryptoOrdersResponse = fixture("orders.json");
+    assert_eq!(resp.next, None);
+    assert_eq!(resp.previous, Some(Cursor::new("cj0xJnA9MjAyNA==")));
+    let [filled, canceled, rejected] = &resp.results[..] else { panic!("expected three orders") };
+
+    assert_eq!(filled.id, "665dd0a1-5b3e-4d8c-9a7e-2c1f0b9e4a11");
+    assert_eq!(filled.client_order_id, "b7e5d0c4-51a3-4c2f-8f0e-7a1d3c9b2e60");
+    assert_eq!((&filled.side, &filled.order_type, &filled.state), (&OrderSide::Buy, &OrderType::Market, &OrderState::Filled));
+    assert_eq!(filled.average_price, Some(d("64014.072")));
+    assert_eq!(filled.filled_asset_quantity, Some(d("0.005")));
+    assert_eq!(filled.executions.len(), 2);
+    assert_eq!(filled.executions[0].effective_price, d("64010.12"));
+    assert_eq!(filled.executions[1].quantity, d("0.002"));
+    assert_eq!(filled.total_filled_quantity(), d("0.005"));
+    assert_eq!(filled.average_fill_price(), Some(d("64014.072")));
+    assert_eq!(filled.market_order_config.as_ref().unwrap().asset_quantity, d("0.005"));
+    assert!(filled.limit_order_config.is_none() && filled.stop_limit_order_config.is_none());
+    assert_eq!(filled.reject_reason, None);
+
+    assert_eq!((&canceled.order_type, &canceled.state), (&OrderType::StopLimit, &OrderState::Canceled));
+    assert!(canceled.executions.is_empty());
+    assert_eq!(canceled.average_price, None);
+    assert_eq!(canceled.filled_asset_quantity, Some(Decimal::ZERO));
+    assert_eq!(canceled.average_fill_price(), None);
+    let stop_limit = canceled.stop_limit_order_config.as_ref().unwrap();
+    assert_eq!(stop_limit.asset_quantity, Some(d("500")));
+    assert_eq!(stop_limit.quote_amount, None);
+    assert_eq!(stop_limit.limit_price, Some(d("0.15")));
+    assert_eq!(stop_limit.stop_price, Some(d("0.152")));
+    assert_eq!(stop_limit.time_in_force, Some(TimeInForce::Gtc));
+
+    assert_eq!(rejected.state, OrderState::Rejected);
+    assert_eq!(rejected.reject_reason.as_deref(), Some("insufficient buying power"));
+    assert_eq!((rejected.average_price, rejected.filled_asset_quantity), (None, None));
+    let limit = rejected.limit_order_config.as_ref().unwrap();
+    assert_eq!((limit.quote_amount, limit.asset_quantity), (Some(d("5000")), None));
+    assert_eq!(limit.time_in_force, Some(TimeInForce::Gfd));
+
+    // absent optional fields stay absent when written back out
+    let json = serde_json::to_value(filled).unwrap();
+    assert!(json.get("reject_reason").is_none());
+    assert_eq!(json["type"], "market");
+    assert_eq!(json["average_price"], "64014.072");
+}
+
+#[test]
+fn test_open_order_without_fills_fixture() {
+    // the same body comes back from creating an order and from looking it up
+    let order: CryptoOrder = fixture("order_open_no_fills.json");
+    assert_eq!(order.state, OrderState::Open);
+    assert!(!order.state.is_terminal());
+    assert!(order.executions.is_empty());
+    assert_eq!((order.average_price, order.filled_asset_quantity), (None, None));
+    assert_eq!(order.total_filled_quantity(), Decimal::ZERO);
+    let limit = order.limit_order_config.as_ref().unwrap();
+    assert_eq!((limit.asset_quantity, limit.limit_price), (Some(d("100")), Some(d("0.48"))));
+
+    let created: CreateCryptoOrderResponse = fixture("order_open_no_fills.json");
+    assert_eq!(created.id, order.id);
+    assert_eq!((created.average_price, created.filled_asset_quantity), (None, None));
+    assert_eq!(CreateCryptoOrderResponse::from(order), created);
+
+    let json = serde_json::to_value(&created).unwrap();
+    assert!(json["average_price"].is_null() && json["filled_asset_quantity"].is_null());
+}
+
+#[test]
+fn test_cancel_order_fixture() {
+    let cancel: CancelOrderResponse = serde_json::from_str(include_str!("fixtures/cancel_order.json")).unwrap();
+    assert_eq!(cancel.message, "Cancel request has been submitted for order 665dd0d9-1a2b-4c3d-8e4f-5a6b7c8d9e44");
+}